@@ -1,26 +1,36 @@
 use std::collections::HashSet;
 
 use macroquad::{
-    camera::{Camera2D, set_camera, set_default_camera},
-    color::Color,
-    input::{MouseButton, is_mouse_button_pressed, mouse_position},
-    math::{Vec2, vec2},
+    camera::{Camera2D, pop_camera_state, push_camera_state, set_camera},
+    color::{Color, WHITE},
+    input::{
+        KeyCode, MouseButton, get_char_pressed, is_key_pressed, is_mouse_button_down,
+        is_mouse_button_pressed, is_quit_requested, mouse_position, prevent_quit,
+    },
+    math::{Rect, Vec2, vec2},
+    rand::{self, RandGenerator},
     shapes::draw_rectangle,
-    texture::{FilterMode, RenderTarget, render_target},
-    time::get_frame_time,
+    texture::{DrawTextureParams, FilterMode, RenderTarget, draw_texture_ex, render_target},
+    time::{get_frame_time, get_time},
     window::{clear_background, screen_height, screen_width},
 };
 
 use crate::blur::BlurPipeline;
+use crate::lantern::LanternPipeline;
 use crate::window_chrome::WindowChrome;
 
 use crate::{
     block::{Block, BlockState},
+    block_registry::block_registry,
+    board_shape::{BoardShape, HEX_HEIGHT_RATIO},
+    confirmation::Confirmation,
     constants::{
-        physics::FORCE,
+        cascade::CLEAR_DELAY,
+        lantern::{DARKNESS_COLOR, FALLOFF},
+        simulation::TIME_STEP,
         style::{
             BACKGROUND_COLOR, BLOCK_DETAIL_MIN_SIZE, BLOCK_INSET, BLOCK_SHADOW_FACTOR,
-            EMPTY_BLOCK_COLOR, GRID_BACKGROUND_COLOR,
+            DEPTH_PIP_COLOR, EMPTY_BLOCK_COLOR, GRID_BACKGROUND_COLOR, HIDDEN_BLOCK_COLOR,
         },
         ui::{
             BLOCK_CORNER_RADIUS_FACTOR, BLOCK_DETAIL_FULL_SIZE, BLOCK_GAP, CHROME_HEIGHT,
@@ -29,86 +39,186 @@ use crate::{
     },
     coordinate::{Coordinate, coordinate},
     difficulty::Difficulty,
-    drawing::{draw_rounded_rect, draw_rounded_rect_asymmetric},
+    drawing::{draw_hex_pixelated, draw_rounded_rect, draw_rounded_rect_asymmetric},
     game_session::{GameSession, GameState},
-    game_ui::{ButtonId, GameUi, compute_status_panel_height},
+    game_settings::GameSettings,
+    game_ui::{ButtonId, GameUi, SliderId, compute_status_panel_height},
+    gravity::Gravity,
     grid_layout::GridLayout,
     grid_size::GridSize,
     high_scores::HighScores,
-    orientation::Orientation,
-    physics_system::PhysicsSystem,
+    level::Level,
+    level_editor::LevelEditor,
+    level_pack::{load_custom_levels, load_starter_pack},
+    level_progress::LevelProgress,
+    particle_system::ParticleSystem,
+    score_bucket::ScoreBucket,
+    score_popups::ScorePopups,
+    scoring::ScoreBreakdown,
     sprite_sheet::SpriteSheet,
+    transition::Transition,
+    transition_kind::TransitionKind,
 };
 
 #[derive(Copy, Clone, PartialEq)]
 pub enum AppState {
     Playing,
+    /// The pause overlay, over the board the game was paused on
+    Paused,
     GameOver,
     MainMenu,
     Settings,
     HighScores,
+    LevelSelect,
+    Editor,
+    EditorSetup,
+    /// Asks before an action that loses progress, over the screen it was taken from
+    Confirm(Confirmation),
 }
 
 pub struct App {
-    state: AppState,
+    /// Open screens, the one shown last. The main menu is always at the bottom.
+    screens: Vec<AppState>,
     sprite_sheet: SpriteSheet,
     ui: GameUi,
     window_chrome: WindowChrome,
     current_session: Option<GameSession>,
-    grid_size: GridSize,
-    difficulty: Difficulty,
-    orientation: Orientation,
+    /// The game set aside while a level is test-played from the editor, resumable after
+    suspended_session: Option<GameSession>,
+    settings: GameSettings,
     last_screen_size: Vec2,
     high_scores: HighScores,
+    levels: Vec<Level>,
+    custom_levels: Vec<Level>,
+    level_progress: LevelProgress,
+    editor: Option<LevelEditor>,
     blur_pipeline: BlurPipeline,
+    lantern_pipeline: LanternPipeline,
+    particle_system: ParticleSystem,
+    score_popups: ScorePopups,
     game_snapshot: Option<RenderTarget>,
+    /// The change of screen in progress, during which input is ignored
+    transition: Option<Transition>,
+    /// Whether the window should close at the end of this frame
+    should_quit: bool,
 }
 
 impl App {
     pub fn new() -> Self {
-        let app_state = AppState::MainMenu;
-        let grid_size = GridSize::default();
-        let difficulty = Difficulty::default();
-        let orientation = Orientation::default();
+        let settings = GameSettings::default();
         let ui = GameUi::new();
         let panel_h = compute_status_panel_height(ui.title_font(), ui.body_font());
-        let (rows, cols) = grid_size.grid_dims(orientation);
+        let (rows, cols) = settings.grid_size.grid_dims(settings.orientation);
+        let grid_extent = settings.rules.shape.extent(rows, cols);
+        // Closing the window is asked about first while a game is in progress
+        prevent_quit();
+        let levels = load_starter_pack();
+        let custom_levels = load_custom_levels();
+        let level_progress = LevelProgress::load(&[levels.as_slice(), &custom_levels].concat());
         Self {
-            state: app_state,
-            sprite_sheet: SpriteSheet::new(include_bytes!("../assets/sprites.png"), 2, 4, 512.0),
-            window_chrome: WindowChrome::new(rows, cols, panel_h),
+            screens: vec![AppState::MainMenu],
+            sprite_sheet: SpriteSheet::new(
+                include_bytes!("../assets/sprites.png"),
+                block_registry().sprite_size,
+            ),
+            window_chrome: WindowChrome::new(grid_extent, panel_h),
             ui,
             current_session: None,
-            grid_size,
-            difficulty,
-            orientation,
+            suspended_session: None,
+            settings,
             last_screen_size: Vec2::ZERO,
             high_scores: HighScores::load(),
+            levels,
+            custom_levels,
+            level_progress,
+            editor: None,
             blur_pipeline: BlurPipeline::new(),
+            lantern_pipeline: LanternPipeline::new(),
+            particle_system: ParticleSystem::new(),
+            score_popups: ScorePopups::new(),
             game_snapshot: None,
+            transition: None,
+            should_quit: false,
         }
     }
 
     pub fn handle_input(&mut self) -> (InputEvent, FrameState) {
-        self.window_chrome
-            .handle_input(self.state == AppState::Playing || self.state == AppState::GameOver);
+        self.window_chrome.handle_input(matches!(
+            self.state(),
+            AppState::Playing | AppState::Paused | AppState::GameOver
+        ));
 
         let mut frame_state = FrameState::default();
         let mut input_event = InputEvent::None;
 
+        if self.transition.is_some() {
+            return (input_event, frame_state);
+        }
+
         let (_, my) = mouse_position();
 
-        if self.state == AppState::Playing {
+        if self.state() == AppState::Playing {
             if let Some(session) = &self.current_session {
                 if is_mouse_button_pressed(MouseButton::Left) && my >= CHROME_HEIGHT {
                     input_event = InputEvent::BlockClicked(mouse_position().into());
+                } else if matches!(session.state, GameState::Cascading) {
+                    frame_state.hovered_blocks = session.pending_cascade.clone();
+                } else if session.is_busy() {
+                    // Groups can't be known until the board settles, so only the block a
+                    // click would land on is highlighted
+                    frame_state.hovered_blocks = session
+                        .block_under(mouse_position().into())
+                        .map(|(position, _)| position)
+                        .into_iter()
+                        .collect();
                 } else if let Some(position) = session.layout.world_to_grid(mouse_position().into())
                 {
-                    frame_state.hovered_blocks = session.layout.get_block_region(position);
+                    frame_state.hovered_blocks = session.clearable_region(position);
+                }
+            }
+        }
+
+        if self.state() == AppState::Editor
+            && let Some(editor) = &mut self.editor
+        {
+            if !is_mouse_button_down(MouseButton::Left) {
+                editor.end_stroke();
+            }
+
+            let position = editor
+                .preview
+                .layout
+                .world_to_grid(mouse_position().into())
+                .filter(|_| my >= CHROME_HEIGHT);
+            if let Some(position) = position {
+                if is_mouse_button_down(MouseButton::Left) {
+                    input_event = InputEvent::EditorBrush {
+                        position,
+                        starts_stroke: is_mouse_button_pressed(MouseButton::Left),
+                    };
+                } else {
+                    frame_state.hovered_blocks = editor.affected_cells(position);
                 }
             }
         }
 
+        if self.state() == AppState::EditorSetup
+            && let Some(editor) = &mut self.editor
+        {
+            let mut renamed = false;
+            while let Some(c) = get_char_pressed() {
+                editor.type_name_char(c);
+                renamed = true;
+            }
+            if is_key_pressed(KeyCode::Backspace) {
+                editor.erase_name_char();
+                renamed = true;
+            }
+            if renamed {
+                self.refresh_ui();
+            }
+        }
+
         if let Some(button_id) = self.ui.handle_input() {
             input_event = InputEvent::UIButton(button_id);
         }
@@ -117,22 +227,30 @@ impl App {
     }
 
     pub fn update(&mut self, input: InputEvent) {
+        if is_quit_requested() {
+            // Closing the window again while being asked quits anyway
+            if self.state() == AppState::Confirm(Confirmation::Quit) {
+                self.should_quit = true;
+            } else {
+                self.confirm(Confirmation::Quit);
+            }
+        }
+
+        if let Some(transition) = &mut self.transition
+            && !transition.update(get_frame_time())
+        {
+            self.transition = None;
+        }
+
         let current_size = vec2(screen_width(), screen_height());
         if current_size != self.last_screen_size {
             self.last_screen_size = current_size;
-            if self.state == AppState::GameOver {
+            if self.shows_board_backdrop() {
                 self.game_snapshot = None;
             }
-            self.ui.update_buttons(
-                self.state,
-                self.current_session.is_some(),
-                self.grid_size,
-                self.difficulty,
-                self.orientation,
-                &self.high_scores,
-            );
+            self.refresh_ui();
 
-            if self.state == AppState::Playing || self.state == AppState::GameOver {
+            if self.state() == AppState::Playing || self.shows_board_backdrop() {
                 if let Some(session) = &mut self.current_session {
                     let panel_h =
                         compute_status_panel_height(self.ui.title_font(), self.ui.body_font());
@@ -140,167 +258,422 @@ impl App {
                         current_size.x,
                         current_size.y,
                         panel_h,
-                        session.layout.rows,
-                        session.layout.cols,
+                        session.layout.extent(),
                     );
                     session.layout.resize(pos, dims);
+                    self.particle_system.clear();
                 }
             }
         }
 
         match input {
             InputEvent::BlockClicked(pos) => {
-                if self.state == AppState::Playing {
+                if self.state() == AppState::Playing {
                     if let Some(session) = &mut self.current_session {
-                        let blocks_removed = session.layout.remove_block_region(pos);
-                        session.score += App::calculate_points(blocks_removed);
+                        // Applied by the next step that finds the board settled
+                        session.queue_click(pos);
                     }
                 }
             }
+            InputEvent::EditorBrush {
+                position,
+                starts_stroke,
+            } => {
+                if let Some(editor) = &mut self.editor {
+                    editor.apply_tool(position, starts_stroke);
+                }
+            }
             InputEvent::UIButton(button_id) => match button_id {
-                ButtonId::Menu => self.set_state(AppState::MainMenu),
-                ButtonId::NewGame => self.new_game(),
-                ButtonId::Pause => self.set_state(AppState::MainMenu),
+                ButtonId::Menu => self.open_screen(AppState::MainMenu),
+                ButtonId::NewGame => self.confirm(Confirmation::NewGame),
+                ButtonId::Pause => {
+                    if self.is_test_play() {
+                        self.open_screen(AppState::Editor);
+                    } else {
+                        self.push_screen(AppState::Paused);
+                    }
+                }
+                ButtonId::Restart => self.confirm(Confirmation::Restart),
+                ButtonId::Confirm(confirmation) => self.take_confirmed(confirmation),
                 ButtonId::Resume => {
-                    self.set_state(AppState::Playing);
-                    let grid_dims = self
-                        .current_session
-                        .as_ref()
-                        .map(|s| (s.layout.rows, s.layout.cols));
-                    if let Some((rows, cols)) = grid_dims {
+                    self.open_screen(AppState::Playing);
+                    let grid_extent = self.current_session.as_ref().map(|s| s.layout.extent());
+                    if let Some(grid_extent) = grid_extent {
                         let sw = screen_width();
                         let sh = screen_height();
-                        self.fit_window_to_grid(sw, sh, rows, cols);
+                        self.fit_window_to_grid(sw, sh, grid_extent);
                     }
                 }
-                ButtonId::Settings => self.set_state(AppState::Settings),
-                ButtonId::HighScores => self.set_state(AppState::HighScores),
-                ButtonId::Back => self.set_state(AppState::MainMenu),
+                ButtonId::Settings => self.push_screen(AppState::Settings),
+                ButtonId::HighScores => self.push_screen(AppState::HighScores),
+                ButtonId::Levels => self.push_screen(AppState::LevelSelect),
+                ButtonId::PlayLevel(index) => self.confirm(Confirmation::PlayLevel(index)),
+                ButtonId::PlayCustomLevel(index) => {
+                    self.confirm(Confirmation::PlayCustomLevel(index))
+                }
+                ButtonId::Editor => {
+                    self.editor.get_or_insert_with(LevelEditor::new);
+                    self.open_screen(AppState::Editor);
+                }
+                ButtonId::LevelSetup => self.push_screen(AppState::EditorSetup),
+                ButtonId::TestLevel => self.test_level(),
+                ButtonId::SaveLevel => {
+                    self.update_editor(LevelEditor::save);
+                    self.custom_levels = load_custom_levels();
+                }
+                ButtonId::Undo => self.update_editor(LevelEditor::undo),
+                ButtonId::SetBrush(block_type) => {
+                    self.update_editor(|editor| editor.brush = block_type)
+                }
+                ButtonId::SetEditorTool(tool) => self.update_editor(|editor| editor.tool = tool),
+                ButtonId::SetLevelRules(rules) => {
+                    self.update_editor(|editor| editor.edit(|level| level.rules = rules))
+                }
+                ButtonId::SetLevelGoal(goal) => {
+                    self.update_editor(|editor| editor.edit(|level| level.goal = goal))
+                }
+                ButtonId::AdjustLevel(setting, steps) => {
+                    self.update_editor(|editor| editor.adjust(setting, steps))
+                }
+                ButtonId::Back => self.pop_screen(),
                 ButtonId::SetGridSize(s) => {
-                    self.grid_size = s;
-                    self.ui.update_buttons(
-                        self.state,
-                        self.current_session.is_some(),
-                        self.grid_size,
-                        self.difficulty,
-                        self.orientation,
-                        &self.high_scores,
-                    );
+                    self.settings.grid_size = s;
+                    self.refresh_ui();
                 }
                 ButtonId::SetDifficulty(d) => {
-                    self.difficulty = d;
-                    self.ui.update_buttons(
-                        self.state,
-                        self.current_session.is_some(),
-                        self.grid_size,
-                        self.difficulty,
-                        self.orientation,
-                        &self.high_scores,
-                    );
+                    self.settings.difficulty = d;
+                    self.refresh_ui();
+                }
+                ButtonId::Slide(slider, value) => {
+                    let settings = &mut self.settings;
+                    match (slider, &mut settings.grid_size, &mut settings.difficulty) {
+                        (SliderId::GridRows, GridSize::Custom { rows, .. }, _) => *rows = value,
+                        (SliderId::GridCols, GridSize::Custom { cols, .. }, _) => *cols = value,
+                        (SliderId::Colors, _, Difficulty::Custom { colors }) => *colors = value,
+                        _ => {}
+                    }
+                    self.refresh_ui();
                 }
                 ButtonId::SetOrientation(o) => {
-                    self.orientation = o;
-                    self.ui.update_buttons(
-                        self.state,
-                        self.current_session.is_some(),
-                        self.grid_size,
-                        self.difficulty,
-                        self.orientation,
-                        &self.high_scores,
-                    );
+                    self.settings.orientation = o;
+                    self.refresh_ui();
+                }
+                ButtonId::SetGameMode(m) => {
+                    self.settings.rules.mode = m;
+                    self.refresh_ui();
+                }
+                ButtonId::SetScoringRule(r) => {
+                    self.settings.rules.scoring = r;
+                    self.refresh_ui();
+                }
+                ButtonId::SetConnectivity(c) => {
+                    self.settings.rules.connectivity = c;
+                    self.refresh_ui();
+                }
+                ButtonId::SetGravity(g) => {
+                    self.settings.gravity = g;
+                    self.refresh_ui();
+                }
+                ButtonId::SetBoardShape(s) => {
+                    self.settings.rules = self.settings.rules.with_shape(s);
+                    self.refresh_ui();
+                }
+                ButtonId::SetBoardDepth(d) => {
+                    self.settings.rules.depth = d;
+                    self.refresh_ui();
+                }
+                ButtonId::SetHaunted(h) => {
+                    self.settings.rules.haunted = h;
+                    self.refresh_ui();
+                }
+                ButtonId::SetGuaranteedClear(g) => {
+                    self.settings.rules.guaranteed_clear = g;
+                    self.refresh_ui();
+                }
+                ButtonId::SetBoardStyle(style) => {
+                    self.settings.rules.style = style;
+                    self.refresh_ui();
+                }
+                ButtonId::SetEasing(easing) => {
+                    self.settings.easing = easing;
+                    self.refresh_ui();
+                }
+                ButtonId::SetLandingBounce(bounce) => {
+                    self.settings.landing_bounce = bounce;
+                    self.refresh_ui();
+                }
+                ButtonId::SetSettleMode(mode) => {
+                    self.settings.settle_mode = mode;
+                    self.refresh_ui();
+                }
+                ButtonId::SetClearEffect(effect) => {
+                    self.settings.clear_effect = effect;
+                    self.refresh_ui();
                 }
             },
             InputEvent::None => {}
         }
 
-        if self.state == AppState::Playing {
-            if let Some(session) = &self.current_session
-                && session.is_game_over()
-            {
-                self.high_scores
-                    .record(session.grid_size, session.difficulty, session.score);
-                self.set_state(AppState::GameOver);
-            };
+        if self.state() == AppState::Playing {
+            let mut game_over = false;
 
             if let Some(session) = &mut self.current_session {
-                match session.state {
-                    GameState::Playing => {
-                        if session.is_game_over() {
-                            // already handled above
-                        } else if let Some(falling_blocks) = session.layout.find_falling_blocks() {
-                            falling_blocks.into_iter().for_each(|(from, to)| {
-                                session.physics_system.queue_block_animation(from, to)
-                            });
-                            session.state = GameState::BlocksFalling;
-                        } else if let Some(shifting_blocks) = session.layout.find_shifting_blocks()
-                        {
-                            shifting_blocks.into_iter().for_each(|(from, to)| {
-                                session.physics_system.queue_block_animation(from, to)
-                            });
-                            session.state = GameState::ColumnsShifting;
-                        }
-                    }
-                    GameState::BlocksFalling => {
-                        let blocks_still_falling = session.physics_system.update(
-                            &mut session.layout,
-                            vec2(0.0, FORCE),
-                            get_frame_time(),
-                        );
+                for _ in 0..session.clock.advance(get_frame_time()) {
+                    game_over = App::step_session(session);
 
-                        if !blocks_still_falling {
-                            if let Some(shifting_blocks) = session.layout.find_shifting_blocks() {
-                                shifting_blocks.into_iter().for_each(|(from, to)| {
-                                    session.physics_system.queue_block_animation(from, to)
-                                });
-                                session.state = GameState::ColumnsShifting;
-                            } else {
-                                session.state = GameState::Playing;
-                            };
-                        }
-                    }
-                    GameState::ColumnsShifting => {
-                        let blocks_still_shifting = session.physics_system.update(
-                            &mut session.layout,
-                            vec2(-FORCE, 0.0),
-                            get_frame_time(),
+                    for group in session.take_cleared_groups() {
+                        let blocks = group
+                            .blocks
+                            .iter()
+                            .map(|(position, block_type)| {
+                                (session.layout.cell_center(*position), block_type.clone())
+                            })
+                            .collect::<Vec<_>>();
+                        self.particle_system.spawn_clear(
+                            self.settings.clear_effect,
+                            &blocks,
+                            session.layout.block_size - BLOCK_GAP,
                         );
+                        self.score_popups.spawn(&session.layout, &group);
+                    }
+                    self.particle_system.update(TIME_STEP);
+                    self.score_popups.update(TIME_STEP);
 
-                        if !blocks_still_shifting {
-                            session.state = GameState::Playing;
-                        };
+                    if game_over {
+                        break;
                     }
                 }
             }
+
+            if game_over {
+                self.finish_game();
+            }
         }
     }
 
-    pub fn render(&mut self, frame_state: FrameState) {
-        clear_background(BACKGROUND_COLOR);
+    /// Simulates one fixed step of the game. Returns true once the game is over.
+    fn step_session(session: &mut GameSession) -> bool {
+        session.elapsed += TIME_STEP;
+        session.layout.update_block_animations(TIME_STEP);
 
-        if self.state == AppState::GameOver {
-            if self.game_snapshot.is_none() {
-                self.take_game_snapshot();
+        match session.state {
+            GameState::Playing => {
+                let settling_blocks = session
+                    .settle_mode
+                    .combines_moves()
+                    .then(|| session.layout.find_settling_blocks(session.gravity))
+                    .flatten();
+                if let Some(settling_blocks) = settling_blocks {
+                    // Falls and shifts play out as one move, leaving nothing to shift
+                    // once the blocks land
+                    session.queue_moves(settling_blocks);
+                    session.state = GameState::BlocksFalling;
+                } else if let Some(falling_blocks) =
+                    session.layout.find_falling_blocks(session.gravity)
+                {
+                    session.queue_moves(falling_blocks);
+                    session.state = GameState::BlocksFalling;
+                } else if let Some(shifting_blocks) =
+                    session.layout.find_shifting_blocks(session.gravity)
+                {
+                    session.queue_moves(shifting_blocks);
+                    session.state = GameState::ColumnsShifting;
+                } else if session.haunt_if_due() {
+                    // Blocks flicker into their new types; the board is checked again
+                    // next step
+                } else if session.start_cascade() {
+                    session.cascade_timer = CLEAR_DELAY;
+                    session.state = GameState::Cascading;
+                } else if session.reveal_if_stuck() {
+                    // The lights come on; the revealed board is checked next step
+                } else if session.apply_queued_click() {
+                    // Blocks fall into the gaps next step
+                } else if session.is_game_over() {
+                    return true;
+                }
             }
-            let snapshot_texture = self.game_snapshot.as_ref().map(|s| s.texture.clone());
-            if let Some(texture) = snapshot_texture {
-                let sw = screen_width();
-                let sh = screen_height();
-                self.blur_pipeline.apply(&texture, sw, sh);
-                draw_rectangle(0.0, 0.0, sw, sh, MODAL_SCRIM_COLOR);
+            GameState::BlocksFalling => {
+                let blocks_still_falling = session.physics_system.update(
+                    &mut session.layout,
+                    session.gravity.fall_force(),
+                    TIME_STEP,
+                );
+
+                if !blocks_still_falling {
+                    if let Some(shifting_blocks) =
+                        session.layout.find_shifting_blocks(session.gravity)
+                    {
+                        session.queue_moves(shifting_blocks);
+                        session.state = GameState::ColumnsShifting;
+                    } else {
+                        session.state = GameState::Playing;
+                    };
+                }
+            }
+            GameState::ColumnsShifting => {
+                let blocks_still_shifting = session.physics_system.update(
+                    &mut session.layout,
+                    session.gravity.shift_force(),
+                    TIME_STEP,
+                );
+
+                if !blocks_still_shifting {
+                    session.state = GameState::Playing;
+                };
+            }
+            GameState::Cascading => {
+                session.cascade_timer -= TIME_STEP;
+                if session.cascade_timer <= 0.0 {
+                    session.clear_cascade();
+                    // Blocks fall into the gaps as usual, then settle and check again
+                    session.state = GameState::Playing;
+                }
             }
-        } else if self.state == AppState::Playing {
+        }
+
+        false
+    }
+
+    fn finish_game(&mut self) {
+        let is_test_play = self.is_test_play();
+        if let Some(session) = &mut self.current_session {
+            session.scoring.finish(session.layout.blocks_remaining);
+            if is_test_play {
+                // Test plays from the editor aren't recorded
+            } else if let (Some(level), Some(stars)) = (&session.level, session.level_stars()) {
+                // Levels are rated with stars instead of competing for high scores
+                self.level_progress.record(level, stars);
+            } else {
+                let bucket = ScoreBucket::new(session.grid_size, session.difficulty, session.rules);
+                self.high_scores.record(bucket, session.score());
+            }
+        }
+        self.push_screen(AppState::GameOver);
+    }
+
+    pub fn render(&mut self, frame_state: FrameState) {
+        self.draw_screen(&frame_state.hovered_blocks);
+        if let Some(transition) = &self.transition {
+            transition.render();
+        }
+
+        self.window_chrome.render(self.ui.body_font());
+    }
+
+    /// Draws the current screen, everything but the window chrome
+    fn draw_screen(&mut self, hovered_blocks: &HashSet<Coordinate>) {
+        clear_background(BACKGROUND_COLOR);
+
+        if self.shows_board_backdrop() {
+            self.draw_board_backdrop();
+        } else if self.state() == AppState::Playing {
             if let Some(session) = &self.current_session {
                 App::draw_grid_background(session);
-                App::draw_blocks(&self.sprite_sheet, session, &frame_state.hovered_blocks);
+                App::draw_blocks(&self.sprite_sheet, session, hovered_blocks);
+                self.particle_system.render(&self.sprite_sheet);
+                if session.rules.mode.has_lantern() {
+                    self.draw_lantern(session, mouse_position().into());
+                }
+                // Above the lantern's darkness, so points are never hidden
+                self.score_popups.render(
+                    &session.layout,
+                    self.ui.body_font(),
+                    self.ui.title_font(),
+                );
+            }
+        } else if self.state() == AppState::Editor {
+            if let Some(editor) = &self.editor {
+                App::draw_grid_background(&editor.preview);
+                App::draw_blocks(&self.sprite_sheet, &editor.preview, hovered_blocks);
             }
         }
 
         self.ui.render(UiContext {
+            sprite_sheet: &self.sprite_sheet,
             score: self.score(),
             blocks_remaining: self.blocks_remaining(),
+            chain: self.chain(),
+            score_breakdown: self.score_breakdown(),
+            game_time: self
+                .current_session
+                .as_ref()
+                .map_or(0.0, |session| session.elapsed),
         });
+    }
 
-        self.window_chrome.render(self.ui.body_font());
+    /// Whether the current screen is drawn over the board it was left from. A game left for
+    /// the main menu stays behind it, out of focus.
+    fn shows_board_backdrop(&self) -> bool {
+        self.current_session.is_some()
+            && !matches!(
+                self.state(),
+                AppState::Playing | AppState::Editor | AppState::EditorSetup
+            )
+    }
+
+    /// Draws the board blurred and dimmed behind a modal or menu. While blurring in, the
+    /// sharp board fades out over it.
+    fn draw_board_backdrop(&mut self) {
+        if self.game_snapshot.is_none() {
+            self.take_game_snapshot();
+        }
+        let Some(texture) = self.game_snapshot.as_ref().map(|s| s.texture.clone()) else {
+            return;
+        };
+
+        let sw = screen_width();
+        let sh = screen_height();
+        self.blur_pipeline.apply(&texture, sw, sh);
+
+        let focus = match &self.transition {
+            Some(transition) if transition.kind == TransitionKind::BlurIn => {
+                1.0 - transition.progress()
+            }
+            _ => 0.0,
+        };
+        if focus > 0.0 {
+            draw_texture_ex(
+                &texture,
+                0.0,
+                0.0,
+                Color { a: focus, ..WHITE },
+                DrawTextureParams {
+                    dest_size: Some(vec2(sw, sh)),
+                    flip_y: true,
+                    ..Default::default()
+                },
+            );
+        }
+        draw_rectangle(
+            0.0,
+            0.0,
+            sw,
+            sh,
+            Color {
+                a: MODAL_SCRIM_COLOR.a * (1.0 - focus),
+                ..MODAL_SCRIM_COLOR
+            },
+        );
+    }
+
+    /// Draws the current screen into a texture, for a transition away from it
+    fn capture_screen(&mut self) -> RenderTarget {
+        let sw = screen_width();
+        let sh = screen_height();
+        let rt = render_target(sw as u32, sh as u32);
+        rt.texture.set_filter(FilterMode::Linear);
+
+        push_camera_state();
+        set_camera(&Camera2D {
+            render_target: Some(rt.clone()),
+            zoom: vec2(2.0 / sw, -2.0 / sh),
+            target: vec2(sw / 2.0, sh / 2.0),
+            ..Default::default()
+        });
+        self.draw_screen(&HashSet::new());
+        pop_camera_state();
+
+        rt
     }
 
     fn take_game_snapshot(&mut self) {
@@ -309,6 +682,7 @@ impl App {
         let rt = render_target(sw as u32, sh as u32);
         rt.texture.set_filter(FilterMode::Linear);
 
+        push_camera_state();
         set_camera(&Camera2D {
             render_target: Some(rt.clone()),
             zoom: vec2(2.0 / sw, -2.0 / sh),
@@ -322,29 +696,181 @@ impl App {
             App::draw_blocks(&self.sprite_sheet, session, &HashSet::new());
         }
 
-        set_default_camera();
+        pop_camera_state();
         self.game_snapshot = Some(rt);
     }
 
-    pub fn set_state(&mut self, state: AppState) {
-        if self.state == AppState::GameOver && state == AppState::MainMenu {
+    /// The screen on top of the stack, the one being shown
+    fn state(&self) -> AppState {
+        *self.screens.last().expect("the main menu is never popped")
+    }
+
+    /// Opens `state` on top of the current screen
+    fn push_screen(&mut self, state: AppState) {
+        self.navigate(|screens| screens.push(state));
+    }
+
+    /// Closes the current screen, returning to the one it was opened from
+    fn pop_screen(&mut self) {
+        self.navigate(|screens| {
+            screens.pop();
+        });
+    }
+
+    /// Returns to `state` if it's already open, closing everything above it, and opens it
+    /// otherwise
+    fn open_screen(&mut self, state: AppState) {
+        self.navigate(|screens| match screens.iter().position(|s| *s == state) {
+            Some(index) => screens.truncate(index + 1),
+            None => screens.push(state),
+        });
+    }
+
+    /// Changes the screen stack, then transitions to whichever screen ends up on top
+    fn navigate(&mut self, change: impl FnOnce(&mut Vec<AppState>)) {
+        let previous = self.state();
+        let mut screens = self.screens.clone();
+        change(&mut screens);
+        if screens.is_empty() {
+            screens.push(AppState::MainMenu);
+        }
+        let state = *screens.last().unwrap();
+
+        if state != previous {
+            let outgoing = self.capture_screen();
+            self.transition = Some(Transition::new(
+                TransitionKind::between(previous, state),
+                outgoing,
+            ));
+        }
+        self.screens = screens;
+
+        if previous == AppState::GameOver && state == AppState::MainMenu {
             self.current_session = None;
             self.game_snapshot = None;
         }
 
-        if state == AppState::GameOver {
+        // The board is about to change, or be shown again as it is now
+        if state == AppState::GameOver || state == AppState::Playing {
             self.game_snapshot = None;
         }
 
-        self.state = state;
+        // Leaving a test play discards it, bringing back the game it set aside
+        if state == AppState::Editor
+            && let Some(editor) = &mut self.editor
+            && editor.is_testing
+        {
+            editor.is_testing = false;
+            self.current_session = self.suspended_session.take();
+            self.game_snapshot = None;
+        }
+
+        self.refresh_ui();
+    }
+
+    fn refresh_ui(&mut self) {
         self.ui.update_buttons(
-            state,
-            self.current_session.is_some(),
-            self.grid_size,
-            self.difficulty,
-            self.orientation,
-            &self.high_scores,
+            self.state(),
+            LayoutContext {
+                is_existing_game: self.current_session.is_some(),
+                settings: self.settings,
+                high_scores: &self.high_scores,
+                levels: &self.levels,
+                custom_levels: &self.custom_levels,
+                level_progress: &self.level_progress,
+                editor: self.editor.as_ref(),
+                level_stars: self.level_stars(),
+                confirmation: match self.state() {
+                    AppState::Confirm(confirmation) => Some(confirmation),
+                    _ => None,
+                },
+            },
         );
+
+        // The editor's board fills whatever space its toolbar leaves
+        if self.state() == AppState::Editor
+            && let Some(editor) = &mut self.editor
+        {
+            let panel_h = self.ui.status_panel_height();
+            let (pos, dims) = compute_grid_rect(
+                screen_width(),
+                screen_height(),
+                panel_h,
+                editor.grid_extent(),
+            );
+            editor.resize_preview(pos, dims);
+        }
+    }
+
+    /// Changes the level editor, then refreshes its screen
+    fn update_editor(&mut self, change: impl FnOnce(&mut LevelEditor)) {
+        if let Some(editor) = &mut self.editor {
+            change(editor);
+        }
+        self.refresh_ui();
+    }
+
+    /// Takes `action` straight away, or asks first if it would lose a game in progress
+    fn confirm(&mut self, action: Confirmation) {
+        if self.has_game_in_progress() {
+            self.push_screen(AppState::Confirm(action));
+        } else {
+            self.take_confirmed(action);
+        }
+    }
+
+    fn take_confirmed(&mut self, action: Confirmation) {
+        match action {
+            Confirmation::NewGame => self.new_game(),
+            Confirmation::Restart => self.restart_game(),
+            Confirmation::PlayLevel(index) => {
+                if let Some(level) = self.levels.get(index).cloned() {
+                    self.start_level(level);
+                }
+            }
+            Confirmation::PlayCustomLevel(index) => {
+                if let Some(level) = self.custom_levels.get(index).cloned() {
+                    self.start_level(level);
+                }
+            }
+            Confirmation::Quit => self.should_quit = true,
+        }
+    }
+
+    /// Whether there's a game that would be lost by starting another or quitting. Finished
+    /// games and test plays from the editor don't count, though a game set aside for a test
+    /// play does.
+    fn has_game_in_progress(&self) -> bool {
+        if self.is_test_play() {
+            return self.suspended_session.is_some();
+        }
+        self.current_session.is_some() && !self.screens.contains(&AppState::GameOver)
+    }
+
+    /// Whether the window should close, after the player quit
+    pub fn should_quit(&self) -> bool {
+        self.should_quit
+    }
+
+    fn is_test_play(&self) -> bool {
+        self.editor.as_ref().is_some_and(|editor| editor.is_testing)
+    }
+
+    fn test_level(&mut self) {
+        let Some(editor) = &mut self.editor else {
+            return;
+        };
+
+        if let Err(e) = editor.validate() {
+            editor.status = Some(e);
+            self.refresh_ui();
+            return;
+        }
+
+        editor.is_testing = true;
+        let level = editor.level.clone();
+        self.suspended_session = self.current_session.take();
+        self.start_level(level);
     }
 
     fn draw_grid_background(session: &GameSession) {
@@ -358,11 +884,35 @@ impl App {
         );
     }
 
+    /// Leaves only the blocks around `center` lit. The game over snapshot is drawn without
+    /// it so the final board is shown in full.
+    fn draw_lantern(&self, session: &GameSession, center: Vec2) {
+        let block_size = session.layout.block_size;
+        let area = Rect::new(
+            session.layout.x() - CONTAINER_INNER_PADDING,
+            session.layout.y() - CONTAINER_INNER_PADDING,
+            session.layout.width() + CONTAINER_INNER_PADDING * 2.0,
+            session.layout.height() + CONTAINER_INNER_PADDING * 2.0,
+        );
+        self.lantern_pipeline.apply(
+            area,
+            center,
+            session.difficulty.lantern_radius() * block_size,
+            FALLOFF * block_size,
+            DARKNESS_COLOR,
+        );
+    }
+
     fn draw_blocks(
         sprite_sheet: &SpriteSheet,
         session: &GameSession,
         hovered_blocks: &HashSet<Coordinate>,
     ) {
+        if session.layout.shape == BoardShape::Hex {
+            App::draw_hex_blocks(sprite_sheet, session, hovered_blocks);
+            return;
+        }
+
         let block_size = session.layout.block_size;
         let half_gap = BLOCK_GAP / 2.0;
         let render_size = block_size - BLOCK_GAP;
@@ -386,30 +936,103 @@ impl App {
             }
         }
 
-        // Pass 2: block sprites on top
-        for row in 0..session.layout.rows {
-            for col in 0..session.layout.cols {
-                let position = coordinate(row, col);
-                if let Some(block) = session.layout.get_block(position) {
-                    let block_state = if hovered_blocks.contains(&position) {
-                        BlockState::Hover
-                    } else {
-                        BlockState::Default
-                    };
+        // Pass 2: block sprites on top, bottom layer first so each stack's topmost block
+        // covers the ones beneath it
+        for layer in 0..session.layout.layers {
+            for row in 0..session.layout.rows {
+                for col in 0..session.layout.cols {
+                    let position = coordinate(row, col);
+                    if let Some(block) = session.layout.get_block_at(position, layer) {
+                        let block_state =
+                            App::block_state(session, hovered_blocks, position, layer);
+                        let world_pos = session.layout.grid_to_world(position);
+                        let anim_offset = session.physics_system.get_animation_offset(
+                            position,
+                            layer,
+                            session.clock.alpha(),
+                        );
+                        App::draw_block(
+                            sprite_sheet,
+                            block,
+                            block_state,
+                            world_pos + vec2(half_gap, half_gap) + anim_offset,
+                            render_size,
+                        );
+                    }
+                }
+            }
+        }
+
+        // Pass 3: depth pips along the top edge of each stacked cell
+        if session.layout.layers > 1 {
+            let pip_size = App::depth_pip_size(render_size);
+            for row in 0..session.layout.rows {
+                for col in 0..session.layout.cols {
+                    let position = coordinate(row, col);
                     let world_pos = session.layout.grid_to_world(position);
-                    let anim_offset = session.physics_system.get_animation_offset(position);
-                    App::draw_block(
-                        sprite_sheet,
-                        block,
-                        block_state,
-                        world_pos + vec2(half_gap, half_gap) + anim_offset,
-                        render_size,
+                    App::draw_depth_pips(
+                        session,
+                        position,
+                        world_pos + vec2(half_gap + render_size / 2.0, half_gap + pip_size),
+                        pip_size,
                     );
                 }
             }
         }
     }
 
+    /// Only the topmost block of a stack can be hovered
+    fn block_state(
+        session: &GameSession,
+        hovered_blocks: &HashSet<Coordinate>,
+        position: Coordinate,
+        layer: u32,
+    ) -> BlockState {
+        if hovered_blocks.contains(&position) && session.layout.top_layer(position) == Some(layer) {
+            BlockState::Hover
+        } else {
+            BlockState::Default
+        }
+    }
+
+    fn depth_pip_size(render_size: f32) -> f32 {
+        (render_size * 0.08).round().max(2.0)
+    }
+
+    /// Draws one pip for each block stacked beneath the topmost one, centered on `top_center`.
+    /// Cells with a block in motion are skipped until it lands.
+    fn draw_depth_pips(
+        session: &GameSession,
+        position: Coordinate,
+        top_center: Vec2,
+        pip_size: f32,
+    ) {
+        let Some(top_layer) = session.layout.top_layer(position) else {
+            return;
+        };
+        if top_layer == 0
+            || session.physics_system.get_animation_offset(
+                position,
+                top_layer,
+                session.clock.alpha(),
+            ) != Vec2::ZERO
+        {
+            return;
+        }
+
+        let spacing = pip_size * 2.0;
+        let row_width = spacing * (top_layer - 1) as f32 + pip_size;
+        for i in 0..top_layer {
+            draw_rectangle(
+                top_center.x - row_width / 2.0 + spacing * i as f32,
+                top_center.y,
+                pip_size,
+                pip_size,
+                DEPTH_PIP_COLOR,
+            );
+        }
+    }
+
     fn draw_block(
         sprite_sheet: &SpriteSheet,
         block: &Block,
@@ -417,23 +1040,34 @@ impl App {
         position: Vec2,
         size: f32,
     ) {
-        let darken = match state {
-            BlockState::Default => 1.0,
-            BlockState::Hover => 0.6,
-        };
-        let block_color = block.block_type.get_color();
-        let shadow_color = Color::new(
-            block_color.r * BLOCK_SHADOW_FACTOR * darken,
-            block_color.g * BLOCK_SHADOW_FACTOR * darken,
-            block_color.b * BLOCK_SHADOW_FACTOR * darken,
-            1.0,
-        );
-        let fill_color = Color::new(
-            block_color.r * darken,
-            block_color.g * darken,
-            block_color.b * darken,
-            1.0,
+        if block.hidden || block.is_revealing() {
+            App::draw_block_tile(
+                position,
+                size,
+                HIDDEN_BLOCK_COLOR,
+                App::shadow_of(HIDDEN_BLOCK_COLOR),
+            );
+            if block.hidden {
+                return;
+            }
+        }
+
+        // Newly revealed blocks grow out of the middle of their hidden tile
+        let scale = App::reveal_scale(block);
+        let position = position + Vec2::splat(size * (1.0 - scale) / 2.0);
+        let size = size * scale;
+
+        let (darken, fill_color, shadow_color) = App::block_colors(block, state);
+        App::draw_block_tile(position, size, fill_color, shadow_color);
+        sprite_sheet.render_sprite(
+            block.block_type.get_sprite_id(),
+            position,
+            size,
+            Color::new(darken, darken, darken, 1.0),
         );
+    }
+
+    fn draw_block_tile(position: Vec2, size: f32, fill_color: Color, shadow_color: Color) {
         let roundness = ((size - BLOCK_DETAIL_MIN_SIZE)
             / (BLOCK_DETAIL_FULL_SIZE - BLOCK_DETAIL_MIN_SIZE))
             .clamp(0.0, 1.0);
@@ -458,17 +1092,150 @@ impl App {
             inner_bottom_r,
             fill_color,
         );
+    }
+
+    /// Eased size of a block as it grows out of its hidden tile
+    fn reveal_scale(block: &Block) -> f32 {
+        let t = block.reveal_progress;
+        1.0 - (1.0 - t) * (1.0 - t)
+    }
+
+    /// Haunted blocks shimmer faintly, and flicker hard while changing type
+    fn haunt_brightness(block: &Block) -> f32 {
+        if block.flicker > 0.0 {
+            0.35 + 0.65 * (block.flicker * 40.0).sin().abs()
+        } else if block.haunted {
+            0.9 + 0.1 * (get_time() as f32 * 3.0).sin()
+        } else {
+            1.0
+        }
+    }
+
+    fn shadow_of(color: Color) -> Color {
+        Color::new(
+            color.r * BLOCK_SHADOW_FACTOR,
+            color.g * BLOCK_SHADOW_FACTOR,
+            color.b * BLOCK_SHADOW_FACTOR,
+            1.0,
+        )
+    }
+
+    /// Returns the darken factor, fill color and shadow color for a block in `state`
+    fn block_colors(block: &Block, state: BlockState) -> (f32, Color, Color) {
+        let darken = match state {
+            BlockState::Default => 1.0,
+            BlockState::Hover => 0.6,
+        } * App::haunt_brightness(block);
+        let block_color = block.block_type.get_color();
+        let shadow_color = Color::new(
+            block_color.r * BLOCK_SHADOW_FACTOR * darken,
+            block_color.g * BLOCK_SHADOW_FACTOR * darken,
+            block_color.b * BLOCK_SHADOW_FACTOR * darken,
+            1.0,
+        );
+        let fill_color = Color::new(
+            block_color.r * darken,
+            block_color.g * darken,
+            block_color.b * darken,
+            1.0,
+        );
+        (darken, fill_color, shadow_color)
+    }
+
+    fn draw_hex_blocks(
+        sprite_sheet: &SpriteSheet,
+        session: &GameSession,
+        hovered_blocks: &HashSet<Coordinate>,
+    ) {
+        let radius = (session.layout.block_size - BLOCK_GAP) / 2.0;
+
+        // Pass 1: empty cell backgrounds for every cell
+        for row in 0..session.layout.rows {
+            for col in 0..session.layout.cols {
+                let center = session.layout.cell_center(coordinate(row, col));
+                draw_hex_pixelated(center.x, center.y, radius, EMPTY_BLOCK_COLOR);
+            }
+        }
+
+        // Pass 2: block sprites on top, bottom layer first
+        for layer in 0..session.layout.layers {
+            for row in 0..session.layout.rows {
+                for col in 0..session.layout.cols {
+                    let position = coordinate(row, col);
+                    if let Some(block) = session.layout.get_block_at(position, layer) {
+                        let block_state =
+                            App::block_state(session, hovered_blocks, position, layer);
+                        let center = session.layout.cell_center(position)
+                            + session.physics_system.get_animation_offset(
+                                position,
+                                layer,
+                                session.clock.alpha(),
+                            );
+                        App::draw_hex_block(sprite_sheet, block, block_state, center, radius);
+                    }
+                }
+            }
+        }
+
+        // Pass 3: depth pips just inside each stacked cell's flat top edge
+        if session.layout.layers > 1 {
+            let pip_size = App::depth_pip_size(radius * 2.0);
+            for row in 0..session.layout.rows {
+                for col in 0..session.layout.cols {
+                    let position = coordinate(row, col);
+                    let center = session.layout.cell_center(position);
+                    App::draw_depth_pips(
+                        session,
+                        position,
+                        center - vec2(0.0, radius * HEX_HEIGHT_RATIO - pip_size),
+                        pip_size,
+                    );
+                }
+            }
+        }
+    }
+
+    fn draw_hex_block(
+        sprite_sheet: &SpriteSheet,
+        block: &Block,
+        state: BlockState,
+        center: Vec2,
+        radius: f32,
+    ) {
+        if block.hidden || block.is_revealing() {
+            App::draw_hex_tile(
+                center,
+                radius,
+                HIDDEN_BLOCK_COLOR,
+                App::shadow_of(HIDDEN_BLOCK_COLOR),
+            );
+            if block.hidden {
+                return;
+            }
+        }
+
+        let radius = radius * App::reveal_scale(block);
+        let (darken, fill_color, shadow_color) = App::block_colors(block, state);
+        App::draw_hex_tile(center, radius, fill_color, shadow_color);
+
+        // The sprite fits within the hex's flat top and bottom edges
+        let sprite_size = radius * HEX_HEIGHT_RATIO * 1.6;
         sprite_sheet.render_sprite(
             block.block_type.get_sprite_id(),
-            position,
-            size,
+            center - Vec2::splat(sprite_size / 2.0),
+            sprite_size,
             Color::new(darken, darken, darken, 1.0),
         );
     }
 
-    /// Calculate points using (n-1)^2 formula
-    fn calculate_points(number_of_blocks: u32) -> u32 {
-        (number_of_blocks.saturating_sub(1)).pow(2)
+    fn draw_hex_tile(center: Vec2, radius: f32, fill_color: Color, shadow_color: Color) {
+        draw_hex_pixelated(center.x, center.y, radius, shadow_color);
+        draw_hex_pixelated(
+            center.x,
+            center.y - BLOCK_INSET / 2.0,
+            radius - BLOCK_INSET / 2.0,
+            fill_color,
+        );
     }
 
     pub fn blocks_remaining(&self) -> u32 {
@@ -481,32 +1248,103 @@ impl App {
     pub fn score(&self) -> u32 {
         self.current_session
             .as_ref()
-            .map(|session| session.score)
+            .map(|session| session.score())
             .unwrap_or(0)
     }
 
+    /// Current cascade chain, if the game is being played in a mode with cascades
+    pub fn chain(&self) -> Option<u32> {
+        self.current_session
+            .as_ref()
+            .filter(|session| session.rules.mode.cascade_group_size().is_some())
+            .map(|session| session.chain)
+    }
+
+    pub fn score_breakdown(&self) -> ScoreBreakdown {
+        self.current_session
+            .as_ref()
+            .map(|session| session.scoring.breakdown())
+            .unwrap_or_default()
+    }
+
+    pub fn level_stars(&self) -> Option<u8> {
+        if self.state() != AppState::GameOver {
+            return None;
+        }
+
+        self.current_session
+            .as_ref()
+            .and_then(|session| session.level_stars())
+    }
+
     pub fn new_game(&mut self) {
-        self.set_state(AppState::Playing);
+        let seed = ((rand::rand() as u64) << 32) | rand::rand() as u64;
+        self.start_game(self.settings, seed);
+    }
+
+    /// Deals a random board from `seed`, so the same seed and settings deal the same game
+    fn start_game(&mut self, settings: GameSettings, seed: u64) {
+        let (rows, cols) = settings.grid_size.grid_dims(settings.orientation);
+        let (pos, dims) = self.prepare_board(settings.rules.shape.extent(rows, cols));
+        let rng = RandGenerator::new();
+        rng.srand(seed);
+        let layout = GridLayout::new(pos, dims, &settings, &rng);
+        let mut session = GameSession::new(settings, layout, rng);
+        session.origin = Some((settings, seed));
+        self.current_session = Some(session);
+    }
+
+    /// Starts the current game over: the same level, or the same random board
+    fn restart_game(&mut self) {
+        let Some(session) = &self.current_session else {
+            return;
+        };
+        if let Some(level) = session.level.clone() {
+            self.start_level(level);
+        } else if let Some((settings, seed)) = session.origin {
+            self.start_game(settings, seed);
+        }
+    }
+
+    fn start_level(&mut self, level: Level) {
+        let (pos, dims) = self.prepare_board(level.rules.shape.extent(level.rows, level.cols));
+        let layout = GridLayout::from_level(pos, dims, &level);
+        let settings = GameSettings {
+            rules: level.rules,
+            gravity: Gravity::default(),
+            ..self.settings
+        };
+        let mut session = GameSession::new(settings, layout, RandGenerator::new());
+        session.level = Some(level);
+        self.current_session = Some(session);
+    }
+
+    /// Switches to the playing screen and fits the window to a board of `grid_extent`.
+    /// Returns the position and size of the board.
+    fn prepare_board(&mut self, grid_extent: Vec2) -> (Vec2, Vec2) {
+        if self.is_test_play() {
+            // Test plays sit over the editor they return to
+            self.open_screen(AppState::Playing);
+        } else {
+            self.navigate(|screens| {
+                screens.truncate(1);
+                screens.push(AppState::Playing);
+            });
+        }
+        self.particle_system.clear();
+        self.score_popups.clear();
         let sw = screen_width();
         let sh = screen_height();
-        let (rows, cols) = self.grid_size.grid_dims(self.orientation);
-        self.fit_window_to_grid(sw, sh, rows, cols);
+        self.fit_window_to_grid(sw, sh, grid_extent);
         let panel_h = self.ui.status_panel_height();
-        let (pos, dims) = compute_grid_rect(screen_width(), screen_height(), panel_h, rows, cols);
-        self.current_session = Some(GameSession {
-            state: GameState::Playing,
-            grid_size: self.grid_size,
-            difficulty: self.difficulty,
-            layout: GridLayout::new(pos, dims, rows, cols, self.difficulty.block_type_count()),
-            score: 0,
-            physics_system: PhysicsSystem::new(),
-        });
+        compute_grid_rect(screen_width(), screen_height(), panel_h, grid_extent)
     }
 
-    fn fit_window_to_grid(&mut self, sw: f32, sh: f32, rows: u32, cols: u32) {
+    fn fit_window_to_grid(&mut self, sw: f32, sh: f32, grid_extent: Vec2) {
         let panel_h = compute_status_panel_height(self.ui.title_font(), self.ui.body_font());
+        let orientation = self.settings.orientation.fitting(grid_extent);
         self.window_chrome
-            .fit_to_grid(sw, sh, rows, cols, panel_h, self.orientation);
+            .fit_to_grid(sw, sh, grid_extent, panel_h, orientation);
     }
 }
 
@@ -514,8 +1352,7 @@ fn compute_grid_rect(
     screen_w: f32,
     screen_h: f32,
     status_panel_h: f32,
-    rows: u32,
-    cols: u32,
+    grid_extent: Vec2,
 ) -> (Vec2, Vec2) {
     let panel_y = screen_h - status_panel_h;
     let container_x = WINDOW_PADDING.x;
@@ -524,9 +1361,9 @@ fn compute_grid_rect(
     let container_h = panel_y - WINDOW_PADDING.y - container_y;
     let available_w = container_w - CONTAINER_INNER_PADDING * 2.0;
     let available_h = container_h - CONTAINER_INNER_PADDING * 2.0;
-    let block_size = (available_w / cols as f32).min(available_h / rows as f32);
-    let grid_w = cols as f32 * block_size;
-    let grid_h = rows as f32 * block_size;
+    let block_size = (available_w / grid_extent.x).min(available_h / grid_extent.y);
+    let grid_w = grid_extent.x * block_size;
+    let grid_h = grid_extent.y * block_size;
     let grid_x = container_x + (container_w - grid_w) / 2.0;
     let grid_y = container_y + (container_h - grid_h) / 2.0;
     (Vec2::new(grid_x, grid_y), Vec2::new(grid_w, grid_h))
@@ -535,12 +1372,37 @@ fn compute_grid_rect(
 pub enum InputEvent {
     None,
     BlockClicked(Vec2),
+    /// The mouse button is down over a cell of the level editor's board
+    EditorBrush {
+        position: Coordinate,
+        starts_stroke: bool,
+    },
     UIButton(ButtonId),
 }
 
-pub struct UiContext {
+pub struct UiContext<'a> {
+    pub sprite_sheet: &'a SpriteSheet,
     pub score: u32,
+    /// Seconds the current game has been played
+    pub game_time: f32,
     pub blocks_remaining: u32,
+    pub chain: Option<u32>,
+    pub score_breakdown: ScoreBreakdown,
+}
+
+/// Everything the screens are laid out from
+pub struct LayoutContext<'a> {
+    pub is_existing_game: bool,
+    pub settings: GameSettings,
+    pub high_scores: &'a HighScores,
+    pub levels: &'a [Level],
+    pub custom_levels: &'a [Level],
+    pub level_progress: &'a LevelProgress,
+    pub editor: Option<&'a LevelEditor>,
+    /// Stars earned on the level just finished, if the game was a level
+    pub level_stars: Option<u8>,
+    /// The action being confirmed, on the confirm screen
+    pub confirmation: Option<Confirmation>,
 }
 
 #[derive(Default)]
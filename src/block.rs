@@ -1,59 +1,95 @@
 use macroquad::color::Color;
-use strum::EnumIter;
 
-use crate::sprite_sheet::SpriteId;
+use crate::{
+    block_registry::{BlockKind, block_registry},
+    sprite_sheet::SpriteId,
+};
 
 pub enum BlockState {
     Default,
     Hover,
 }
 
-#[derive(EnumIter, Clone, Eq, PartialEq, Hash)]
-pub enum BlockType {
-    Potion,
-    Blood,
-    Ghost,
-    Poison,
-    Coffin,
-    Gravestone,
-    Flame,
-    Brain,
-}
+/// A type of block, as listed in the block manifest
+#[derive(Clone, Default, Eq, PartialEq, Hash)]
+pub struct BlockType(usize);
 
 impl BlockType {
+    /// Every block type, in the order difficulties deal them
+    pub fn all() -> impl Iterator<Item = BlockType> {
+        (0..block_registry().type_count()).map(BlockType)
+    }
+
+    fn kind(&self) -> &'static BlockKind {
+        block_registry().kind(self.0)
+    }
+
+    pub fn label(&self) -> &str {
+        &self.kind().name
+    }
+
+    /// Character representing the block in level files
+    pub fn symbol(&self) -> char {
+        self.kind().symbol
+    }
+
     pub fn get_color(&self) -> Color {
-        match self {
-            BlockType::Potion => Color::from_hex(0x40FF00),
-            BlockType::Blood => Color::from_hex(0xE01F39),
-            BlockType::Ghost => Color::from_hex(0xFFFFFF),
-            BlockType::Poison => Color::from_hex(0x9001FE),
-            BlockType::Coffin => Color::from_hex(0xAA7855),
-            BlockType::Gravestone => Color::from_hex(0x788087),
-            BlockType::Flame => Color::from_hex(0xFFA118),
-            BlockType::Brain => Color::from_hex(0xFF00F2),
-        }
+        self.kind().color
     }
 
     pub fn get_sprite_id(&self) -> SpriteId {
-        match self {
-            BlockType::Brain => SpriteId(0, 0),
-            BlockType::Blood => SpriteId(0, 1),
-            BlockType::Poison => SpriteId(0, 2),
-            BlockType::Ghost => SpriteId(0, 3),
-            BlockType::Gravestone => SpriteId(1, 0),
-            BlockType::Flame => SpriteId(1, 1),
-            BlockType::Potion => SpriteId(1, 2),
-            BlockType::Coffin => SpriteId(1, 3),
-        }
+        self.kind().sprite
+    }
+
+    /// How often the block is dealt relative to the other types
+    pub fn spawn_weight(&self) -> f32 {
+        self.kind().spawn_weight
     }
 }
 
 pub struct Block {
     pub block_type: BlockType,
+    /// Hidden blocks are drawn face down and can't be matched until revealed
+    pub hidden: bool,
+    /// How far the reveal animation has played, from 0.0 to 1.0
+    pub reveal_progress: f32,
+    /// Haunted blocks change type every few moves
+    pub haunted: bool,
+    /// Seconds left of the flicker played when a haunted block changes type
+    pub flicker: f32,
 }
 
 impl Block {
     pub fn new(block_type: BlockType) -> Self {
-        Self { block_type }
+        Self {
+            block_type,
+            hidden: false,
+            reveal_progress: 1.0,
+            haunted: false,
+            flicker: 0.0,
+        }
+    }
+
+    pub fn haunted(block_type: BlockType) -> Self {
+        Self {
+            haunted: true,
+            ..Self::new(block_type)
+        }
+    }
+
+    pub fn hide(&mut self) {
+        self.hidden = true;
+        self.reveal_progress = 0.0;
+    }
+
+    /// Returns true if the block was hidden
+    pub fn reveal(&mut self) -> bool {
+        let was_hidden = self.hidden;
+        self.hidden = false;
+        was_hidden
+    }
+
+    pub fn is_revealing(&self) -> bool {
+        !self.hidden && self.reveal_progress < 1.0
     }
 }
@@ -0,0 +1,11 @@
+use crate::coordinate::Coordinate;
+
+/// A block moving between two cells. Blocks never leave their layer.
+#[derive(Copy, Clone)]
+pub struct BlockMove {
+    pub from: Coordinate,
+    pub to: Coordinate,
+    pub layer: u32,
+    /// Where a block that both falls and shifts in one move lands before shifting
+    pub via: Option<Coordinate>,
+}
@@ -0,0 +1,132 @@
+use std::sync::OnceLock;
+
+use macroquad::color::Color;
+
+use crate::sprite_sheet::SpriteId;
+
+/// Block types bundled with the game
+const MANIFEST: &str = include_str!("../assets/blocks.txt");
+
+/// One type of block, as listed in the manifest
+pub struct BlockKind {
+    pub name: String,
+    /// Character representing the block in level files
+    pub symbol: char,
+    pub color: Color,
+    pub sprite: SpriteId,
+    /// How often the block is dealt relative to the other types
+    pub spawn_weight: f32,
+}
+
+/// Every type of block in the game, read from a manifest so new types only need sprite art
+/// and a line of data.
+///
+/// The manifest is written as `key: value` lines, with one `block:` line per type in the
+/// order difficulties deal them. Lines starting with `#` are comments.
+///
+/// ```text
+/// sprite-size: 512                # pixels per cell of the sprite sheet
+/// block: Potion p 40FF00 1 2 1    # name, symbol, color, sprite row and col, spawn weight
+/// ```
+pub struct BlockRegistry {
+    pub sprite_size: f32,
+    kinds: Vec<BlockKind>,
+}
+
+impl BlockRegistry {
+    pub fn parse(source: &str) -> Result<Self, String> {
+        let mut sprite_size = None;
+        let mut kinds: Vec<BlockKind> = vec![];
+
+        for (index, line) in source.lines().enumerate() {
+            let line_number = index + 1;
+            let line = line.split('#').next().unwrap_or_default().trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            let (key, value) = line
+                .split_once(':')
+                .ok_or_else(|| format!("line {line_number}: expected `key: value`"))?;
+            let value = value.trim();
+            let error = |message: String| format!("line {line_number}: {message}");
+            match key.trim() {
+                "sprite-size" => {
+                    sprite_size = Some(
+                        value
+                            .parse::<f32>()
+                            .map_err(|_| error(format!("invalid sprite size `{value}`")))?,
+                    )
+                }
+                "block" => {
+                    let kind = parse_kind(value).map_err(error)?;
+                    if kinds
+                        .iter()
+                        .any(|other| other.name == kind.name || other.symbol == kind.symbol)
+                    {
+                        return Err(error(format!("`{}` is listed twice", kind.name)));
+                    }
+                    kinds.push(kind);
+                }
+                other => return Err(error(format!("unknown key `{other}`"))),
+            }
+        }
+
+        if kinds.is_empty() {
+            return Err("no blocks listed".to_string());
+        }
+
+        Ok(Self {
+            sprite_size: sprite_size.ok_or("missing `sprite-size`")?,
+            kinds,
+        })
+    }
+
+    pub fn type_count(&self) -> usize {
+        self.kinds.len()
+    }
+
+    pub fn kind(&self, index: usize) -> &BlockKind {
+        &self.kinds[index]
+    }
+}
+
+/// The registry read from the bundled manifest
+pub fn block_registry() -> &'static BlockRegistry {
+    static REGISTRY: OnceLock<BlockRegistry> = OnceLock::new();
+    REGISTRY.get_or_init(|| {
+        BlockRegistry::parse(MANIFEST).unwrap_or_else(|e| panic!("blocks.txt: {e}"))
+    })
+}
+
+fn parse_kind(value: &str) -> Result<BlockKind, String> {
+    let [name, symbol, color, sprite_row, sprite_col, spawn_weight] = value
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .try_into()
+        .map_err(|_| "expected name, symbol, color, sprite row and col, and spawn weight")?;
+
+    let mut symbols = symbol.chars();
+    let symbol = match (symbols.next(), symbols.next()) {
+        (Some(symbol), None) if symbol != '.' && symbol != '#' => symbol,
+        _ => return Err(format!("invalid symbol `{symbol}`")),
+    };
+    let color = u32::from_str_radix(color, 16).map_err(|_| format!("invalid color `{color}`"))?;
+    let sprite_cell = |cell: &str| {
+        cell.parse::<u32>()
+            .map_err(|_| format!("invalid sprite cell `{cell}`"))
+    };
+    let spawn_weight = spawn_weight
+        .parse::<f32>()
+        .ok()
+        .filter(|weight| *weight > 0.0)
+        .ok_or_else(|| format!("invalid spawn weight `{spawn_weight}`"))?;
+
+    Ok(BlockKind {
+        name: name.to_string(),
+        symbol,
+        color: Color::from_hex(color),
+        sprite: SpriteId(sprite_cell(sprite_row)?, sprite_cell(sprite_col)?),
+        spawn_weight,
+    })
+}
@@ -1,5 +1,5 @@
 use macroquad::{
-    camera::{Camera2D, set_camera, set_default_camera},
+    camera::{Camera2D, pop_camera_state, push_camera_state, set_camera},
     color::WHITE,
     material::{Material, MaterialParams, gl_use_default_material, gl_use_material, load_material},
     math::vec2,
@@ -120,6 +120,7 @@ impl BlurPipeline {
         }
 
         // Pass 1: horizontal blur — source → h_target
+        push_camera_state();
         set_camera(&Camera2D {
             render_target: Some(self.h_target.clone()),
             zoom: vec2(2.0 / screen_w, -2.0 / screen_h),
@@ -141,9 +142,9 @@ impl BlurPipeline {
             },
         );
         gl_use_default_material();
-        set_default_camera();
+        pop_camera_state();
 
-        // Pass 2: vertical blur — h_target → current render target (screen)
+        // Pass 2: vertical blur — h_target → current render target
         gl_use_material(&self.v_material);
         self.v_material
             .set_uniform("tex_size", (screen_w, screen_h));
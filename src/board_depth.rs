@@ -0,0 +1,34 @@
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Default, serde::Serialize, serde::Deserialize)]
+pub enum BoardDepth {
+    #[default]
+    Single,
+    Double,
+    Triple,
+}
+
+impl BoardDepth {
+    pub fn label(&self) -> &str {
+        match self {
+            BoardDepth::Single => "Single",
+            BoardDepth::Double => "Double",
+            BoardDepth::Triple => "Triple",
+        }
+    }
+
+    pub fn hint(&self) -> &str {
+        match self {
+            BoardDepth::Single => "1 layer",
+            BoardDepth::Double => "2 layers",
+            BoardDepth::Triple => "3 layers",
+        }
+    }
+
+    /// Number of blocks stacked in each cell at the start of a game
+    pub fn layers(&self) -> u32 {
+        match self {
+            BoardDepth::Single => 1,
+            BoardDepth::Double => 2,
+            BoardDepth::Triple => 3,
+        }
+    }
+}
@@ -0,0 +1,47 @@
+use macroquad::math::{Vec2, vec2};
+use strum::EnumIter;
+
+/// Height of a flat-topped hex cell relative to its width (√3 / 2)
+pub const HEX_HEIGHT_RATIO: f32 = 0.866_025_4;
+
+#[derive(
+    Copy, Clone, PartialEq, Eq, Hash, Default, EnumIter, serde::Serialize, serde::Deserialize,
+)]
+pub enum BoardShape {
+    #[default]
+    Square,
+    /// Flat-topped hexes in columns, with every odd column sitting half a cell lower
+    Hex,
+}
+
+impl BoardShape {
+    pub fn label(&self) -> &str {
+        match self {
+            BoardShape::Square => "Square",
+            BoardShape::Hex => "Hex",
+        }
+    }
+
+    pub fn hint(&self) -> &str {
+        match self {
+            BoardShape::Square => "4 sides",
+            BoardShape::Hex => "6 sides",
+        }
+    }
+
+    /// Size of a rows × cols board, measured in cell widths
+    pub fn extent(&self, rows: u32, cols: u32) -> Vec2 {
+        match self {
+            BoardShape::Square => vec2(cols as f32, rows as f32),
+            BoardShape::Hex => {
+                // Neighboring columns overlap by a quarter cell, and odd columns hang half a
+                // cell below the rest
+                let odd_column_drop = if cols > 1 { 0.5 } else { 0.0 };
+                vec2(
+                    0.75 * cols as f32 + 0.25,
+                    (rows as f32 + odd_column_drop) * HEX_HEIGHT_RATIO,
+                )
+            }
+        }
+    }
+}
@@ -0,0 +1,41 @@
+use strum::EnumIter;
+
+/// The look of a random board, chosen on the settings screen. Each difficulty tunes how
+/// strongly a style shows.
+#[derive(
+    Copy, Clone, PartialEq, Eq, Hash, Default, EnumIter, serde::Serialize, serde::Deserialize,
+)]
+pub enum BoardStyle {
+    /// Whichever style the difficulty prefers
+    #[default]
+    Auto,
+    Uniform,
+    Clustered,
+    Striped,
+    Symmetric,
+    Weighted,
+}
+
+impl BoardStyle {
+    pub fn label(&self) -> &str {
+        match self {
+            BoardStyle::Auto => "Auto",
+            BoardStyle::Uniform => "Uniform",
+            BoardStyle::Clustered => "Clustered",
+            BoardStyle::Striped => "Striped",
+            BoardStyle::Symmetric => "Symmetric",
+            BoardStyle::Weighted => "Weighted",
+        }
+    }
+
+    pub fn hint(&self) -> &str {
+        match self {
+            BoardStyle::Auto => "By difficulty",
+            BoardStyle::Uniform => "Even mix",
+            BoardStyle::Clustered => "Bigger groups",
+            BoardStyle::Striped => "Banded rows",
+            BoardStyle::Symmetric => "Mirrored halves",
+            BoardStyle::Weighted => "Some types rarer",
+        }
+    }
+}
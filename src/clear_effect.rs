@@ -0,0 +1,23 @@
+use strum::EnumIter;
+
+/// How cleared blocks leave the board. Only changes how a clear looks.
+#[derive(Copy, Clone, PartialEq, Default, EnumIter)]
+pub enum ClearEffect {
+    /// Blocks pop and throw out a burst of particles in their color
+    #[default]
+    Burst,
+    /// Blocks fade away where they were
+    Dissolve,
+    /// Blocks vanish at once
+    Off,
+}
+
+impl ClearEffect {
+    pub fn label(&self) -> &str {
+        match self {
+            ClearEffect::Burst => "Burst",
+            ClearEffect::Dissolve => "Dissolve",
+            ClearEffect::Off => "Off",
+        }
+    }
+}
@@ -0,0 +1,314 @@
+use std::cell::Cell;
+use std::collections::HashSet;
+use std::iter;
+
+use macroquad::rand::RandGenerator;
+
+use crate::{
+    block::BlockType,
+    board_shape::BoardShape,
+    constants::generation::{ATTEMPTS, CANDIDATES_PER_STEP, WORK_BUDGET},
+    coordinate::{Coordinate, coordinate},
+    gravity::Gravity,
+    rules::GameRules,
+};
+
+/// Generates a full rows × cols board that can be cleared completely under `rules`, ordered
+/// row by row, top to bottom. Returns `None` if no board was found within the attempt and
+/// work budgets.
+///
+/// Boards are built backwards from an empty board. Each step inserts a group of blocks that,
+/// once the board has settled under `gravity`, is exactly one clearable region. Clearing the
+/// groups in the reverse of the order they were inserted settles the board back through the
+/// same steps until it is empty.
+///
+/// Cascades and haunted blocks would take a game off this route, so callers only ask for
+/// boards under rules without them. Only a single layer of blocks is generated.
+pub fn generate_clearable_board(
+    rows: u32,
+    cols: u32,
+    block_types: &[BlockType],
+    rules: &GameRules,
+    gravity: Gravity,
+    rng: &RandGenerator,
+) -> Option<Vec<Option<BlockType>>> {
+    let builder = BoardBuilder {
+        rows,
+        cols,
+        rules,
+        gravity,
+        min_group_size: rules.mode.min_group_size() as usize,
+        work_left: Cell::new(WORK_BUDGET),
+    };
+
+    let board = (0..ATTEMPTS)
+        .take_while(|_| builder.work_left.get() > 0)
+        .find_map(|_| builder.build(block_types, rng));
+    if board.is_none() {
+        eprintln!("clearable_board: no board found for {rows}×{cols}, falling back to random");
+    }
+    board
+}
+
+/// A settled board, as its occupied columns in the order gravity packs them. Each column is
+/// listed from the edge blocks fall towards.
+type Stacks = Vec<Vec<BlockType>>;
+
+/// Where a group is inserted into a settled board
+enum Insertion {
+    /// A run of blocks within a column, pushing the blocks above it along
+    Run {
+        stack: usize,
+        height: usize,
+        size: usize,
+    },
+    /// A run of blocks standing in a new column, placed before `stack`
+    Column { stack: usize, size: usize },
+    /// One block in each of `size` neighboring columns, all at the same height
+    Row {
+        first: usize,
+        height: usize,
+        size: usize,
+    },
+}
+
+impl Insertion {
+    /// Inserts the group, returning the (stack, height) of each of its blocks
+    fn apply(&self, stacks: &mut Stacks, block_type: &BlockType) -> Vec<(usize, usize)> {
+        match *self {
+            Insertion::Run {
+                stack,
+                height,
+                size,
+            } => {
+                stacks[stack].splice(height..height, iter::repeat_n(block_type.clone(), size));
+                (height..height + size).map(|h| (stack, h)).collect()
+            }
+            Insertion::Column { stack, size } => {
+                stacks.insert(stack, vec![block_type.clone(); size]);
+                (0..size).map(|h| (stack, h)).collect()
+            }
+            Insertion::Row {
+                first,
+                height,
+                size,
+            } => (first..first + size)
+                .map(|stack| {
+                    stacks[stack].insert(height, block_type.clone());
+                    (stack, height)
+                })
+                .collect(),
+        }
+    }
+}
+
+struct BoardBuilder<'a> {
+    rows: u32,
+    cols: u32,
+    rules: &'a GameRules,
+    gravity: Gravity,
+    min_group_size: usize,
+    /// Board cells left to check across every attempt, so large boards give up in time
+    work_left: Cell<usize>,
+}
+
+impl BoardBuilder<'_> {
+    fn build(
+        &self,
+        block_types: &[BlockType],
+        rng: &RandGenerator,
+    ) -> Option<Vec<Option<BlockType>>> {
+        let mut stacks: Stacks = vec![];
+        while !self.is_full(&stacks) {
+            stacks = (0..CANDIDATES_PER_STEP)
+                .find_map(|_| self.insert_group(&stacks, block_types, rng))?;
+        }
+
+        Some(self.settle(&stacks))
+    }
+
+    fn is_full(&self, stacks: &Stacks) -> bool {
+        stacks.len() == self.cols as usize
+            && stacks.iter().all(|stack| stack.len() == self.rows as usize)
+    }
+
+    /// Tries to insert a group at a random spot, returning the settled board if some block
+    /// type forms a region there without touching other blocks of its type
+    fn insert_group(
+        &self,
+        stacks: &Stacks,
+        block_types: &[BlockType],
+        rng: &RandGenerator,
+    ) -> Option<Stacks> {
+        let insertion = self.pick_insertion(stacks, rng)?;
+
+        let start = rng.gen_range(0, block_types.len());
+        (0..block_types.len())
+            .map(|i| &block_types[(start + i) % block_types.len()])
+            .find_map(|block_type| {
+                let mut stacks = stacks.clone();
+                let cells = insertion.apply(&mut stacks, block_type);
+                self.is_exact_region(&stacks, &cells).then_some(stacks)
+            })
+    }
+
+    /// Picks a random spot with room for a group, or `None` if the board has no room left
+    /// that can be filled
+    fn pick_insertion(&self, stacks: &Stacks, rng: &RandGenerator) -> Option<Insertion> {
+        let rows = self.rows as usize;
+        let room = |stack: &Vec<BlockType>| rows - stack.len();
+
+        let runs: Vec<usize> = (0..stacks.len())
+            .filter(|&stack| self.pick_size(room(&stacks[stack]), rng).is_some())
+            .collect();
+        let row_size = self.min_group_size + rng.gen_range(0, 2);
+        let rows_across: Vec<usize> = (0..(stacks.len() + 1).saturating_sub(row_size))
+            .filter(|&first| {
+                stacks[first..first + row_size]
+                    .iter()
+                    .all(|stack| self.leaves_fillable_room(room(stack), 1))
+            })
+            .collect();
+        let can_add_column = stacks.len() < self.cols as usize;
+
+        let kinds = [!runs.is_empty(), !rows_across.is_empty(), can_add_column];
+        let available = kinds.iter().filter(|&&kind| kind).count();
+        if available == 0 {
+            return None;
+        }
+        let kind = kinds
+            .iter()
+            .enumerate()
+            .filter(|&(_, &kind)| kind)
+            .nth(rng.gen_range(0, available))
+            .map(|(i, _)| i)?;
+
+        match kind {
+            0 => {
+                let stack = runs[rng.gen_range(0, runs.len())];
+                let len = stacks[stack].len();
+                Some(Insertion::Run {
+                    stack,
+                    height: rng.gen_range(0, len + 1),
+                    size: self.pick_size(rows - len, rng)?,
+                })
+            }
+            1 => {
+                let first = rows_across[rng.gen_range(0, rows_across.len())];
+                let lowest = stacks[first..first + row_size].iter().map(Vec::len).min()?;
+                Some(Insertion::Row {
+                    first,
+                    height: rng.gen_range(0, lowest + 1),
+                    size: row_size,
+                })
+            }
+            _ => Some(Insertion::Column {
+                stack: rng.gen_range(0, stacks.len() + 1),
+                size: self.pick_size(rows, rng)?,
+            }),
+        }
+    }
+
+    /// Picks how many blocks to stack in a column with `room` free cells, preferring small
+    /// groups
+    fn pick_size(&self, room: usize, rng: &RandGenerator) -> Option<usize> {
+        let sizes: Vec<usize> = (self.min_group_size..=room)
+            .filter(|&size| self.leaves_fillable_room(room, size))
+            .collect();
+        let small = sizes
+            .iter()
+            .take_while(|&&size| size < self.min_group_size + 3)
+            .count();
+        match (small, sizes.len()) {
+            (_, 0) => None,
+            (0, _) => sizes.first().copied(),
+            (small, _) => Some(sizes[rng.gen_range(0, small)]),
+        }
+    }
+
+    /// Whether a column with `room` free cells can still be filled once `size` of them are
+    /// used. A column left with less room than the smallest group could only be filled by
+    /// rows of blocks across columns, which may never line up.
+    fn leaves_fillable_room(&self, room: usize, size: usize) -> bool {
+        size <= room && (size == room || room - size >= self.min_group_size)
+    }
+
+    /// Whether the blocks at `cells` form a region on their own, so clearing it removes
+    /// exactly those blocks
+    fn is_exact_region(&self, stacks: &Stacks, cells: &[(usize, usize)]) -> bool {
+        let cell_count = (self.rows * self.cols) as usize;
+        let Some(work_left) = self.work_left.get().checked_sub(cell_count) else {
+            self.work_left.set(0);
+            return false;
+        };
+        self.work_left.set(work_left);
+
+        let board = self.settle(stacks);
+        let group: HashSet<Coordinate> = cells
+            .iter()
+            .map(|&(stack, height)| self.position(stacks.len(), stack, height))
+            .collect();
+
+        let start = *group.iter().next().expect("groups are never empty");
+        let block_type = &board[self.index(start)];
+        let mut region = HashSet::new();
+        let mut frontier = vec![start];
+        while let Some(position) = frontier.pop() {
+            if !region.contains(&position) && board[self.index(position)] == *block_type {
+                if !group.contains(&position) {
+                    return false;
+                }
+                frontier.extend(self.neighbors(position));
+                region.insert(position);
+            }
+        }
+
+        region == group
+    }
+
+    /// Lays the stacks out as they sit on the board, row by row
+    fn settle(&self, stacks: &Stacks) -> Vec<Option<BlockType>> {
+        let mut board = vec![None; (self.rows * self.cols) as usize];
+        for (stack, blocks) in stacks.iter().enumerate() {
+            for (height, block_type) in blocks.iter().enumerate() {
+                let position = self.position(stacks.len(), stack, height);
+                board[self.index(position)] = Some(block_type.clone());
+            }
+        }
+
+        board
+    }
+
+    /// Where a block ends up on the board, matching how `GridLayout` packs falling blocks and
+    /// shifting columns
+    fn position(&self, stack_count: usize, stack: usize, height: usize) -> Coordinate {
+        let free_cols = self.cols - stack_count as u32;
+        let first_col = match self.gravity {
+            Gravity::DownLeft | Gravity::UpLeft => 0,
+            Gravity::DownRight => free_cols,
+            Gravity::DownCenter => free_cols / 2,
+        };
+        let row = if self.gravity.falls_up() {
+            height as u32
+        } else {
+            self.rows - 1 - height as u32
+        };
+
+        coordinate(row, first_col + stack as u32)
+    }
+
+    fn index(&self, position: Coordinate) -> usize {
+        (position.row * self.cols + position.col) as usize
+    }
+
+    fn neighbors(&self, position: Coordinate) -> Vec<Coordinate> {
+        match self.rules.shape {
+            BoardShape::Square => {
+                position.get_neighbors(self.rows, self.cols, self.rules.connectivity)
+            }
+            BoardShape::Hex => {
+                position.get_hex_neighbors(self.rows, self.cols, self.rules.connectivity)
+            }
+        }
+    }
+}
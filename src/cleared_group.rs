@@ -0,0 +1,9 @@
+use crate::{block::BlockType, coordinate::Coordinate};
+
+/// A group of blocks removed from the board by one clear, kept until the app has played
+/// its effects
+pub struct ClearedGroup {
+    /// Each block removed and the cell it was in
+    pub blocks: Vec<(Coordinate, BlockType)>,
+    pub points: u32,
+}
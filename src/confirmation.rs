@@ -0,0 +1,45 @@
+/// An action that throws away progress, which has to be confirmed before it's taken
+#[derive(Copy, Clone, PartialEq)]
+pub enum Confirmation {
+    /// Starting a new game while one is in progress
+    NewGame,
+    /// Starting the current game over from its first move
+    Restart,
+    /// Playing one of the starter levels, by its index, while a game is in progress
+    PlayLevel(usize),
+    /// Playing one of the player's own levels, by its index, while a game is in progress
+    PlayCustomLevel(usize),
+    /// Closing the window while a game is in progress
+    Quit,
+}
+
+impl Confirmation {
+    pub fn title(&self) -> &str {
+        match self {
+            Confirmation::NewGame => "New game?",
+            Confirmation::Restart => "Restart?",
+            Confirmation::PlayLevel(_) | Confirmation::PlayCustomLevel(_) => "Play level?",
+            Confirmation::Quit => "Quit?",
+        }
+    }
+
+    pub fn message(&self) -> &str {
+        match self {
+            Confirmation::NewGame
+            | Confirmation::Restart
+            | Confirmation::PlayLevel(_)
+            | Confirmation::PlayCustomLevel(_) => "Current progress will be lost.",
+            Confirmation::Quit => "The game in progress will be lost.",
+        }
+    }
+
+    /// Label of the button that goes ahead with the action
+    pub fn action_label(&self) -> &str {
+        match self {
+            Confirmation::NewGame => "New game",
+            Confirmation::Restart => "Restart",
+            Confirmation::PlayLevel(_) | Confirmation::PlayCustomLevel(_) => "Play",
+            Confirmation::Quit => "Quit",
+        }
+    }
+}
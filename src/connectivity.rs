@@ -0,0 +1,40 @@
+use strum::EnumIter;
+
+use crate::board_shape::BoardShape;
+
+#[derive(
+    Copy, Clone, PartialEq, Eq, Hash, Default, EnumIter, serde::Serialize, serde::Deserialize,
+)]
+pub enum Connectivity {
+    /// Blocks connect to the four blocks beside them
+    #[default]
+    Orthogonal,
+    /// Blocks also connect to the four blocks at their corners
+    Diagonal,
+    /// Blocks connect orthogonally, and blocks on opposite edges of the board connect
+    Wraparound,
+}
+
+impl Connectivity {
+    /// Whether this changes how blocks connect on a board of `shape`. Hex cells already
+    /// touch every neighbor along a side, so they have no corners to connect through.
+    pub fn applies_to(&self, shape: BoardShape) -> bool {
+        !(*self == Connectivity::Diagonal && shape == BoardShape::Hex)
+    }
+
+    pub fn label(&self) -> &str {
+        match self {
+            Connectivity::Orthogonal => "Orthogonal",
+            Connectivity::Diagonal => "Diagonal",
+            Connectivity::Wraparound => "Wraparound",
+        }
+    }
+
+    pub fn hint(&self) -> &str {
+        match self {
+            Connectivity::Orthogonal => "4-way",
+            Connectivity::Diagonal => "8-way",
+            Connectivity::Wraparound => "Edges wrap",
+        }
+    }
+}
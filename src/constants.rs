@@ -1,6 +1,154 @@
 pub mod physics {
     /// Force in pixels per second^2 that is applied to moving blocks
     pub const FORCE: f32 = 2000.0;
+    /// Seconds each moving block waits after the one ahead of it in its line starts moving
+    pub const STAGGER: f32 = 0.012;
+    /// Height of the hop a block makes when it lands, as a share of the block size
+    pub const BOUNCE_HEIGHT: f32 = 0.12;
+    /// Seconds a landing hop lasts
+    pub const BOUNCE_DURATION: f32 = 0.14;
+    /// Seconds every move takes in the fast settle mode, before any landing hop
+    pub const FAST_SETTLE_DURATION: f32 = 0.18;
+}
+
+pub mod simulation {
+    /// Seconds the game simulates in each fixed step
+    pub const TIME_STEP: f32 = 1.0 / 120.0;
+    /// Most steps simulated for a single frame. Any time beyond this is dropped.
+    pub const MAX_STEPS_PER_FRAME: u32 = 8;
+}
+
+pub mod particles {
+    /// Particles thrown out by each cleared block
+    pub const PER_BLOCK: usize = 6;
+    /// Most particles a single clear throws out, however large the group
+    pub const MAX_PER_CLEAR: usize = 160;
+    /// Most particles alive at once, so cascades on large boards stay cheap
+    pub const MAX_ALIVE: usize = 480;
+    /// Range of launch speeds, in block sizes per second
+    pub const SPEED: (f32, f32) = (1.5, 5.0);
+    /// Range of particle radii, as a share of the block size
+    pub const SIZE: (f32, f32) = (0.04, 0.1);
+    /// Range of seconds each particle lasts
+    pub const LIFETIME: (f32, f32) = (0.35, 0.7);
+    /// Downward pull on particles, in particle radii per second^2
+    pub const GRAVITY: f32 = 240.0;
+    /// Seconds a cleared block takes to pop away in the burst effect
+    pub const POP_DURATION: f32 = 0.18;
+    /// How much a popping block swells before it shrinks, as a share of its size
+    pub const POP_GROWTH: f32 = 0.15;
+    /// Seconds a cleared block takes to fade away in the dissolve effect
+    pub const DISSOLVE_DURATION: f32 = 0.35;
+}
+
+pub mod popups {
+    use macroquad::color::{Color, WHITE};
+
+    /// Seconds the points for a clear float before they are gone
+    pub const POINTS_DURATION: f32 = 0.9;
+    /// Seconds a popup with a callout lasts
+    pub const CALLOUT_DURATION: f32 = 1.4;
+    /// How far popups float up over their lifetime, in block sizes
+    pub const RISE: f32 = 1.2;
+    pub const POINTS_TEXT_SIZE: u16 = 24;
+    pub const CALLOUT_TEXT_SIZE: u16 = 36;
+    pub const POINTS_COLOR: Color = WHITE;
+    pub const CALLOUT_COLOR: Color = Color::from_hex(0xAA30FE);
+    /// Callouts for large clears, each shown for groups of at least its size. Later entries
+    /// take over from earlier ones.
+    pub const CALLOUTS: &[(u32, &str)] = &[
+        (10, "Grim!"),
+        (15, "Ghastly!"),
+        (22, "Macabre!"),
+        (30, "Apocalyptic!"),
+    ];
+}
+
+pub mod transition {
+    /// Seconds a change of screen takes
+    pub const DURATION: f32 = 0.25;
+}
+
+pub mod scoring {
+    /// Bonus awarded for clearing every block on the board
+    pub const BOARD_CLEAR_BONUS: u32 = 1000;
+    /// Points deducted for each block left on the board when the game ends
+    pub const REMAINING_BLOCK_PENALTY: u32 = 10;
+    /// Smallest group that extends a combo streak
+    pub const COMBO_GROUP_SIZE: u32 = 5;
+}
+
+pub mod cascade {
+    /// Smallest newly formed group that is cleared automatically in cascade mode
+    pub const MIN_GROUP_SIZE: u32 = 4;
+    /// Seconds a chain group stays highlighted before it is cleared
+    pub const CLEAR_DELAY: f32 = 0.35;
+}
+
+pub mod darkness {
+    /// Rows nearest the edge blocks fall towards that start revealed in darkness mode
+    pub const LIT_ROWS: u32 = 2;
+    /// Seconds a newly revealed block takes to grow out of its hidden tile
+    pub const REVEAL_DURATION: f32 = 0.4;
+}
+
+pub mod haunted {
+    /// Haunted blocks change type each time the player makes this many moves
+    pub const MOVES_BETWEEN_HAUNTINGS: u32 = 3;
+    /// Seconds a haunted block flickers after changing type
+    pub const FLICKER_DURATION: f32 = 0.6;
+}
+
+pub mod lantern {
+    use macroquad::color::Color;
+
+    /// Distance in blocks over which the edge of the lantern's light fades to darkness
+    pub const FALLOFF: f32 = 1.5;
+    pub const DARKNESS_COLOR: Color = Color::new(0.02, 0.01, 0.04, 0.94);
+}
+
+pub mod generation {
+    /// Boards the guaranteed clear generator starts over on before giving up
+    pub const ATTEMPTS: u32 = 20;
+    /// Spots tried for each group before the generator starts the board over
+    pub const CANDIDATES_PER_STEP: u32 = 40;
+    /// Board cells the guaranteed clear generator may check in total before giving up, which
+    /// keeps large boards from stalling the game
+    pub const WORK_BUDGET: usize = 10_000_000;
+}
+
+pub mod custom {
+    /// Smallest and largest number of rows or columns a custom grid can have
+    pub const MIN_GRID_SIZE: u32 = 4;
+    pub const MAX_GRID_SIZE: u32 = 40;
+    /// Fewest block types a custom difficulty can deal. The most is every type in the block
+    /// manifest.
+    pub const MIN_COLORS: u32 = 2;
+}
+
+pub mod difficulty {
+    /// Block types the manifest must list before the Nightmare difficulty is offered
+    pub const NIGHTMARE_MIN_BLOCK_TYPES: usize = 10;
+    /// Most block types Nightmare deals
+    pub const NIGHTMARE_MAX_BLOCK_TYPES: usize = 12;
+}
+
+pub mod editor {
+    /// Size of the board a new level starts with
+    pub const DEFAULT_ROWS: u32 = 8;
+    pub const DEFAULT_COLS: u32 = 8;
+    /// Smallest and largest number of rows or columns a level can have
+    pub const MIN_BOARD_SIZE: u32 = 2;
+    pub const MAX_BOARD_SIZE: u32 = 20;
+    /// Final scores needed for the second and third stars on a new level
+    pub const DEFAULT_STAR_SCORES: [u32; 2] = [500, 1000];
+    /// Target given to a level when its goal is switched to reaching a score
+    pub const DEFAULT_TARGET_SCORE: u32 = 1000;
+    /// Amount each press of a score button changes a target or star score by
+    pub const SCORE_STEP: u32 = 50;
+    /// Number of changes that can be undone
+    pub const HISTORY_LIMIT: usize = 100;
+    pub const MAX_NAME_LENGTH: usize = 32;
 }
 
 pub mod style {
@@ -9,6 +157,8 @@ pub mod style {
     pub const BACKGROUND_COLOR: Color = Color::from_hex(0x31263E);
     pub const GRID_BACKGROUND_COLOR: Color = Color::from_hex(0x1A1226);
     pub const EMPTY_BLOCK_COLOR: Color = Color::from_hex(0x2D2340);
+    pub const HIDDEN_BLOCK_COLOR: Color = Color::from_hex(0x3B2E52);
+    pub const DEPTH_PIP_COLOR: Color = Color::new(1.0, 1.0, 1.0, 0.45);
     pub const BLOCK_SHADOW_FACTOR: f32 = 0.6;
     pub const BLOCK_INSET: f32 = 2.0;
     pub const BLOCK_DETAIL_MIN_SIZE: f32 = 16.0;
@@ -34,6 +184,9 @@ pub mod ui {
     pub const LABEL_VALUE_SIZE: u16 = 32;
     pub const LABEL_VALUE_GAP: f32 = 10.0;
 
+    // Scrolling
+    pub const SCROLL_STEP: f32 = 40.0;
+
     // Shape
     pub const CORNER_RADIUS: f32 = 8.0;
     pub const BLOCK_CORNER_RADIUS_FACTOR: f32 = 0.15;
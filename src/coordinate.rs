@@ -1,5 +1,7 @@
 use std::ops::{Add, Sub};
 
+use crate::connectivity::Connectivity;
+
 #[derive(PartialEq, Eq, Hash, Clone, Copy)]
 pub struct Coordinate {
     pub row: u32,
@@ -27,7 +29,16 @@ impl Sub for Coordinate {
 }
 
 impl Coordinate {
-    pub fn get_neighbors(&self, row_bound: u32, col_bound: u32) -> Vec<Coordinate> {
+    pub fn get_neighbors(
+        &self,
+        row_bound: u32,
+        col_bound: u32,
+        connectivity: Connectivity,
+    ) -> Vec<Coordinate> {
+        if connectivity == Connectivity::Wraparound {
+            return self.get_wrapping_neighbors(row_bound, col_bound);
+        }
+
         let &Coordinate { row, col } = self;
         let mut neighbors = vec![];
 
@@ -51,6 +62,122 @@ impl Coordinate {
             neighbors.push(coordinate(row, col + 1));
         }
 
+        if connectivity == Connectivity::Diagonal {
+            // Up-left, up-right, down-left, down-right
+            if row > 0 && col > 0 {
+                neighbors.push(coordinate(row - 1, col - 1));
+            }
+            if row > 0 && col < col_bound - 1 {
+                neighbors.push(coordinate(row - 1, col + 1));
+            }
+            if row < row_bound - 1 && col > 0 {
+                neighbors.push(coordinate(row + 1, col - 1));
+            }
+            if row < row_bound - 1 && col < col_bound - 1 {
+                neighbors.push(coordinate(row + 1, col + 1));
+            }
+        }
+
+        neighbors
+    }
+
+    /// The six neighbors of a cell on a hex board, where odd columns sit half a cell lower.
+    /// Hex cells already touch along every side, so only wraparound changes them. Columns
+    /// only wrap when there's an even number of them, since joining two columns of the same
+    /// parity would leave the cells along the seam out of line.
+    pub fn get_hex_neighbors(
+        &self,
+        row_bound: u32,
+        col_bound: u32,
+        connectivity: Connectivity,
+    ) -> Vec<Coordinate> {
+        let &Coordinate { row, col } = self;
+        // Cells to the side share the row above in even columns and the row below in odd ones
+        let side_row_offsets = if col % 2 == 0 { [-1, 0] } else { [0, 1] };
+        let offsets = [
+            (-1, 0),
+            (1, 0),
+            (side_row_offsets[0], -1),
+            (side_row_offsets[1], -1),
+            (side_row_offsets[0], 1),
+            (side_row_offsets[1], 1),
+        ];
+
+        let wrap_rows = connectivity == Connectivity::Wraparound;
+        let wrap_cols = wrap_rows && col_bound.is_multiple_of(2);
+        let mut neighbors = vec![];
+        for (row_offset, col_offset) in offsets {
+            let Some(neighbor_row) = wrap_index(row as i64 + row_offset, row_bound, wrap_rows)
+            else {
+                continue;
+            };
+            let Some(neighbor_col) = wrap_index(col as i64 + col_offset, col_bound, wrap_cols)
+            else {
+                continue;
+            };
+            let neighbor = coordinate(neighbor_row, neighbor_col);
+
+            if neighbor != *self && !neighbors.contains(&neighbor) {
+                neighbors.push(neighbor);
+            }
+        }
+
         neighbors
     }
+
+    /// Orthogonal neighbors, where each edge of the board wraps around to the opposite edge
+    fn get_wrapping_neighbors(&self, row_bound: u32, col_bound: u32) -> Vec<Coordinate> {
+        let &Coordinate { row, col } = self;
+        let mut neighbors = vec![
+            coordinate((row + row_bound - 1) % row_bound, col),
+            coordinate((row + 1) % row_bound, col),
+            coordinate(row, (col + col_bound - 1) % col_bound),
+            coordinate(row, (col + 1) % col_bound),
+        ];
+
+        // Narrow boards can wrap back onto the same cell
+        neighbors.retain(|neighbor| neighbor != self);
+        neighbors
+    }
+}
+
+/// `index` brought within `0..bound`, wrapping around if `wrap` is set. `None` if it falls
+/// outside and doesn't wrap.
+fn wrap_index(index: i64, bound: u32, wrap: bool) -> Option<u32> {
+    if wrap {
+        Some(index.rem_euclid(bound as i64) as u32)
+    } else {
+        u32::try_from(index).ok().filter(|&index| index < bound)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hex_neighbors_are_symmetric() {
+        let connectivities = [
+            Connectivity::Orthogonal,
+            Connectivity::Diagonal,
+            Connectivity::Wraparound,
+        ];
+        for connectivity in connectivities {
+            for (rows, cols) in [(5, 4), (5, 7), (8, 12), (8, 13), (3, 3), (2, 2)] {
+                for row in 0..rows {
+                    for col in 0..cols {
+                        let a = coordinate(row, col);
+                        for b in a.get_hex_neighbors(rows, cols, connectivity) {
+                            assert!(
+                                b.get_hex_neighbors(rows, cols, connectivity).contains(&a),
+                                "({row},{col}) -> ({},{}) is one-way on {rows}x{cols}",
+                                b.row,
+                                b.col,
+                            );
+                        }
+                    }
+                }
+            }
+        }
+    }
 }
@@ -1,9 +1,23 @@
+use crate::{
+    block_registry::block_registry,
+    board_style::BoardStyle,
+    constants::difficulty::{NIGHTMARE_MAX_BLOCK_TYPES, NIGHTMARE_MIN_BLOCK_TYPES},
+    generation_strategy::GenerationStrategy,
+};
+
 #[derive(Copy, Clone, PartialEq, Eq, Hash, Default, serde::Serialize, serde::Deserialize)]
 pub enum Difficulty {
     Easy,
     #[default]
     Normal,
     Hard,
+    /// Deals every block type in the manifest, up to 12. Only offered once there are at
+    /// least 10.
+    Nightmare,
+    /// Deals `colors` block types, tuned otherwise like the preset with the nearest count
+    Custom {
+        colors: u32,
+    },
 }
 
 impl Difficulty {
@@ -12,6 +26,8 @@ impl Difficulty {
             Difficulty::Easy => "Easy",
             Difficulty::Normal => "Normal",
             Difficulty::Hard => "Hard",
+            Difficulty::Nightmare => "Nightmare",
+            Difficulty::Custom { .. } => "Custom",
         }
     }
 
@@ -20,6 +36,94 @@ impl Difficulty {
             Difficulty::Easy => 4,
             Difficulty::Normal => 6,
             Difficulty::Hard => 8,
+            Difficulty::Nightmare => block_registry().type_count().min(NIGHTMARE_MAX_BLOCK_TYPES),
+            Difficulty::Custom { colors } => *colors as usize,
+        }
+    }
+
+    /// The preset difficulties, including Nightmare when the block manifest lists enough
+    /// types for it
+    pub fn presets() -> Vec<Difficulty> {
+        let mut presets = vec![Difficulty::Easy, Difficulty::Normal, Difficulty::Hard];
+        if block_registry().type_count() >= NIGHTMARE_MIN_BLOCK_TYPES {
+            presets.push(Difficulty::Nightmare);
+        }
+        presets
+    }
+
+    /// A custom difficulty with the same number of block types, for the player to adjust from
+    pub fn as_custom(&self) -> Difficulty {
+        Difficulty::Custom {
+            colors: self.block_type_count() as u32,
+        }
+    }
+
+    /// The preset whose tuning this difficulty follows. Never `Custom`.
+    fn preset(&self) -> Difficulty {
+        match self {
+            Difficulty::Custom { colors: ..=4 } => Difficulty::Easy,
+            Difficulty::Custom { colors: 5..=6 } => Difficulty::Normal,
+            Difficulty::Custom { colors: 7..=8 } => Difficulty::Hard,
+            Difficulty::Custom { .. } => Difficulty::Nightmare,
+            preset => *preset,
+        }
+    }
+
+    /// Chance that each block is generated haunted, when haunted blocks are enabled
+    pub fn haunted_rate(&self) -> f32 {
+        match self.preset() {
+            Difficulty::Easy => 0.04,
+            Difficulty::Normal => 0.07,
+            Difficulty::Hard => 0.1,
+            Difficulty::Nightmare | Difficulty::Custom { .. } => 0.13,
+        }
+    }
+
+    /// How random boards are dealt in `style`. Easier difficulties lean harder into styles
+    /// that form large groups.
+    pub fn generation_strategy(&self, style: BoardStyle) -> GenerationStrategy {
+        let preset = self.preset();
+        match (style, preset) {
+            (BoardStyle::Auto, Difficulty::Easy) => GenerationStrategy::Clustered { bias: 0.3 },
+            (BoardStyle::Auto | BoardStyle::Uniform, _) => GenerationStrategy::Uniform,
+            (BoardStyle::Clustered, _) => GenerationStrategy::Clustered {
+                bias: match preset {
+                    Difficulty::Easy => 0.5,
+                    Difficulty::Normal => 0.35,
+                    Difficulty::Hard => 0.25,
+                    Difficulty::Nightmare | Difficulty::Custom { .. } => 0.2,
+                },
+            },
+            (BoardStyle::Striped, _) => GenerationStrategy::Striped {
+                width: 2,
+                strength: match preset {
+                    Difficulty::Easy => 0.8,
+                    Difficulty::Normal => 0.65,
+                    Difficulty::Hard => 0.5,
+                    Difficulty::Nightmare | Difficulty::Custom { .. } => 0.4,
+                },
+            },
+            (BoardStyle::Symmetric, _) => GenerationStrategy::Symmetric,
+            (BoardStyle::Weighted, _) => GenerationStrategy::Weighted {
+                weights: match preset {
+                    Difficulty::Easy => &[3.0, 2.0, 1.0, 1.0],
+                    Difficulty::Normal => &[3.0, 2.0, 2.0, 1.0, 1.0, 0.5],
+                    Difficulty::Hard => &[2.0, 2.0, 1.5, 1.0, 1.0, 1.0, 0.5, 0.5],
+                    Difficulty::Nightmare | Difficulty::Custom { .. } => {
+                        &[2.0, 1.5, 1.5, 1.0, 1.0, 1.0, 1.0, 1.0, 0.5, 0.5, 0.5, 0.5]
+                    }
+                },
+            },
+        }
+    }
+
+    /// Radius in blocks of the light around the cursor in lantern mode
+    pub fn lantern_radius(&self) -> f32 {
+        match self.preset() {
+            Difficulty::Easy => 3.5,
+            Difficulty::Normal => 2.5,
+            Difficulty::Hard => 1.75,
+            Difficulty::Nightmare | Difficulty::Custom { .. } => 1.5,
         }
     }
 }
@@ -1,5 +1,7 @@
 use macroquad::{color::Color, shapes::draw_rectangle};
 
+use crate::board_shape::HEX_HEIGHT_RATIO;
+
 const PIXEL_SIZE: f32 = 2.0;
 
 pub fn draw_rounded_rect(x: f32, y: f32, w: f32, h: f32, r: f32, color: Color) {
@@ -22,6 +24,29 @@ pub fn draw_circle_pixelated(cx: f32, cy: f32, r: f32, color: Color) {
     draw_rounded_rect(cx - r, cy - r, r * 2.0, r * 2.0, r, color);
 }
 
+/// Draws a flat-topped hexagon with circumradius `r`, built from pixel-sized strips
+pub fn draw_hex_pixelated(cx: f32, cy: f32, r: f32, color: Color) {
+    let half_h = r * HEX_HEIGHT_RATIO;
+    let top = cy - half_h;
+    let step = PIXEL_SIZE;
+
+    let y_steps = (half_h * 2.0 / step).ceil() as i32;
+    for yi in 0..y_steps {
+        let py = top + yi as f32 * step;
+        let strip_mid = py + step * 0.5;
+
+        // Full width across the middle, narrowing along the slanted sides
+        let dy = (strip_mid - cy).abs().min(half_h);
+        let half_w = r - dy * 0.5 / HEX_HEIGHT_RATIO;
+        let half_w = (half_w / step).round() * step;
+
+        let strip_h = step.min(cy + half_h - py);
+        if half_w > 0.0 {
+            draw_rectangle(cx - half_w, py, half_w * 2.0, strip_h, color);
+        }
+    }
+}
+
 fn draw_scanlines(
     x: f32,
     y: f32,
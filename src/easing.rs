@@ -0,0 +1,44 @@
+use strum::EnumIter;
+
+/// How a moving block's speed changes on its way to its new cell. Every curve takes as long
+/// as a block accelerating from rest would, so only the feel of the motion changes.
+#[derive(Copy, Clone, PartialEq, Default, EnumIter)]
+pub enum Easing {
+    /// Speeds up the whole way, like a falling object
+    #[default]
+    Accelerate,
+    Linear,
+    /// Starts fast and slows into place
+    EaseOut,
+    /// Speeds up, then slows into place
+    EaseInOut,
+}
+
+impl Easing {
+    pub fn label(&self) -> &str {
+        match self {
+            Easing::Accelerate => "Gravity",
+            Easing::Linear => "Linear",
+            Easing::EaseOut => "Ease out",
+            Easing::EaseInOut => "Ease in-out",
+        }
+    }
+
+    /// Share of the path covered after `progress` of the move's duration, both from 0.0
+    /// to 1.0
+    pub fn apply(&self, progress: f32) -> f32 {
+        let t = progress.clamp(0.0, 1.0);
+        match self {
+            Easing::Accelerate => t * t,
+            Easing::Linear => t,
+            Easing::EaseOut => 1.0 - (1.0 - t).powi(3),
+            Easing::EaseInOut => {
+                if t < 0.5 {
+                    4.0 * t * t * t
+                } else {
+                    1.0 - (-2.0 * t + 2.0).powi(3) / 2.0
+                }
+            }
+        }
+    }
+}
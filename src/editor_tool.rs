@@ -0,0 +1,23 @@
+use strum::EnumIter;
+
+/// What a click on the board does in the level editor
+#[derive(Copy, Clone, PartialEq, Eq, Default, EnumIter)]
+pub enum EditorTool {
+    /// Paints the brush onto each cell the cursor is dragged over
+    #[default]
+    Paint,
+    /// Paints the brush over the clicked cell and every matching cell connected to it
+    Fill,
+    /// Empties each cell the cursor is dragged over
+    Erase,
+}
+
+impl EditorTool {
+    pub fn label(&self) -> &str {
+        match self {
+            EditorTool::Paint => "Paint",
+            EditorTool::Fill => "Fill",
+            EditorTool::Erase => "Erase",
+        }
+    }
+}
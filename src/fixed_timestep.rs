@@ -0,0 +1,34 @@
+use crate::constants::simulation::{MAX_STEPS_PER_FRAME, TIME_STEP};
+
+/// Splits frame times into steps of `TIME_STEP`, so the simulation plays out the same way
+/// at any frame rate
+pub struct FixedTimestep {
+    /// Seconds of frame time not yet simulated, always less than one step between frames
+    accumulator: f32,
+}
+
+impl FixedTimestep {
+    pub fn new() -> Self {
+        Self { accumulator: 0.0 }
+    }
+
+    /// Adds a frame's time and returns how many steps to simulate for it. Time past
+    /// `MAX_STEPS_PER_FRAME` steps is dropped, so a long frame slows the game down instead
+    /// of making it jump ahead.
+    pub fn advance(&mut self, frame_time: f32) -> u32 {
+        self.accumulator += frame_time;
+        let steps = (self.accumulator / TIME_STEP) as u32;
+        if steps > MAX_STEPS_PER_FRAME {
+            self.accumulator = 0.0;
+            return MAX_STEPS_PER_FRAME;
+        }
+
+        self.accumulator -= steps as f32 * TIME_STEP;
+        steps
+    }
+
+    /// How far the frame being drawn is between the last two steps, from 0.0 to 1.0
+    pub fn alpha(&self) -> f32 {
+        (self.accumulator / TIME_STEP).clamp(0.0, 1.0)
+    }
+}
@@ -0,0 +1,64 @@
+use strum::EnumIter;
+
+use crate::constants::cascade::MIN_GROUP_SIZE;
+
+#[derive(
+    Copy, Clone, PartialEq, Eq, Hash, Default, EnumIter, serde::Serialize, serde::Deserialize,
+)]
+pub enum GameMode {
+    /// Any group can be cleared, including single blocks
+    #[default]
+    Casual,
+    /// Only groups of two or more can be cleared; the game ends when no moves remain
+    Strict,
+    /// Like strict, but newly formed groups clear themselves as a chain after blocks settle
+    Cascade,
+    /// Like casual, but most of the board starts hidden and is revealed as groups are cleared
+    /// and blocks fall past it
+    Darkness,
+    /// Like casual, but only blocks near the cursor can be seen
+    Lantern,
+}
+
+impl GameMode {
+    pub fn label(&self) -> &str {
+        match self {
+            GameMode::Casual => "Casual",
+            GameMode::Strict => "Strict",
+            GameMode::Cascade => "Cascade",
+            GameMode::Darkness => "Darkness",
+            GameMode::Lantern => "Lantern",
+        }
+    }
+
+    /// Smallest group that can be cleared by clicking it
+    pub fn min_group_size(&self) -> u32 {
+        match self {
+            GameMode::Casual | GameMode::Darkness | GameMode::Lantern => 1,
+            GameMode::Strict | GameMode::Cascade => 2,
+        }
+    }
+
+    /// Smallest newly formed group that clears itself, if this mode has chain reactions
+    pub fn cascade_group_size(&self) -> Option<u32> {
+        match self {
+            GameMode::Cascade => Some(MIN_GROUP_SIZE),
+            _ => None,
+        }
+    }
+
+    /// Whether the board starts mostly hidden
+    pub fn starts_dark(&self) -> bool {
+        matches!(self, GameMode::Darkness)
+    }
+
+    /// Whether only the area around the cursor is lit
+    pub fn has_lantern(&self) -> bool {
+        matches!(self, GameMode::Lantern)
+    }
+
+    /// Whether some blocks can't be seen while playing
+    pub fn hides_blocks(&self) -> bool {
+        self.starts_dark() || self.has_lantern()
+    }
+}
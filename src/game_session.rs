@@ -1,29 +1,289 @@
+use std::collections::HashSet;
+
+use macroquad::{math::Vec2, rand::RandGenerator};
+
 use crate::{
-    difficulty::Difficulty, grid_layout::GridLayout, grid_size::GridSize,
+    block_move::BlockMove,
+    cleared_group::ClearedGroup,
+    constants::{darkness::LIT_ROWS, haunted::MOVES_BETWEEN_HAUNTINGS},
+    coordinate::Coordinate,
+    difficulty::Difficulty,
+    fixed_timestep::FixedTimestep,
+    game_settings::GameSettings,
+    gravity::Gravity,
+    grid_layout::GridLayout,
+    grid_size::GridSize,
+    level::Level,
     physics_system::PhysicsSystem,
+    rules::GameRules,
+    scoring::ScoreKeeper,
+    settle_mode::SettleMode,
 };
 
 pub enum GameState {
     Playing,
     BlocksFalling,
     ColumnsShifting,
+    Cascading,
 }
 
 pub struct GameSession {
     pub state: GameState,
-    pub score: u32,
     pub grid_size: GridSize,
     pub difficulty: Difficulty,
+    pub rules: GameRules,
+    pub gravity: Gravity,
+    pub scoring: ScoreKeeper,
     pub layout: GridLayout,
     pub physics_system: PhysicsSystem,
+    /// Steps the simulation at a fixed rate, independent of the frame rate
+    pub clock: FixedTimestep,
+    pub settle_mode: SettleMode,
+    /// Number of cascade steps since the player's last move
+    pub chain: u32,
+    /// Groups about to be cleared by the current cascade step
+    pub pending_cascade: HashSet<Coordinate>,
+    pub cascade_timer: f32,
+    /// Cell of the block the player last clicked, cleared once the board has settled. Moves
+    /// queued before then carry it along with the block. Dropped if the block is cleared or
+    /// changes type first, so another block landing in its cell is never cleared instead.
+    queued_click: Option<Coordinate>,
+    /// Groups cleared since the app last played their clear effects
+    cleared_groups: Vec<ClearedGroup>,
+    /// Cells that blocks have moved into since cascades were last checked
+    moved_blocks: HashSet<Coordinate>,
+    /// Seeded when the board is generated, so the whole game replays the same way
+    rng: RandGenerator,
+    moves_since_haunting: u32,
+    /// The level being played, if this isn't a random board
+    pub level: Option<Level>,
+    /// Settings and seed a random board was dealt from, so the game can be restarted
+    pub origin: Option<(GameSettings, u64)>,
+    /// Seconds of play so far. Only counts while the game is running, so it stops while
+    /// paused.
+    pub elapsed: f32,
 }
 
 impl GameSession {
+    pub fn new(settings: GameSettings, mut layout: GridLayout, rng: RandGenerator) -> Self {
+        // Only boards actually generated to be cleared are played and filed as such
+        let rules = GameRules {
+            guaranteed_clear: layout.is_clearable,
+            ..settings.rules
+        };
+        if settings.rules.mode.starts_dark() {
+            layout.darken(LIT_ROWS, settings.gravity);
+        }
+
+        Self {
+            state: GameState::Playing,
+            grid_size: settings.grid_size,
+            difficulty: settings.difficulty,
+            rules,
+            gravity: settings.gravity,
+            scoring: ScoreKeeper::new(settings.rules.scoring),
+            layout,
+            physics_system: PhysicsSystem::new(
+                settings.easing,
+                settings.landing_bounce,
+                settings.settle_mode,
+            ),
+            clock: FixedTimestep::new(),
+            settle_mode: settings.settle_mode,
+            chain: 0,
+            pending_cascade: HashSet::new(),
+            cascade_timer: 0.0,
+            queued_click: None,
+            cleared_groups: vec![],
+            moved_blocks: HashSet::new(),
+            rng,
+            moves_since_haunting: 0,
+            level: None,
+            origin: None,
+            elapsed: 0.0,
+        }
+    }
+
+    /// Queues a click on the block drawn at `point`, replacing any click still waiting for
+    /// the board to settle
+    pub fn queue_click(&mut self, point: Vec2) {
+        self.queued_click = self.block_under(point).map(|(_, destination)| destination);
+    }
+
+    /// Clears the group the player clicked on, if they clicked. Returns true if there was a
+    /// click. Must only be called once the board has settled.
+    pub fn apply_queued_click(&mut self) -> bool {
+        let Some(position) = self.queued_click.take() else {
+            return false;
+        };
+
+        let removed = self
+            .layout
+            .remove_block_region(position, self.rules.mode.min_group_size());
+        if !removed.is_empty() {
+            self.record_move();
+        }
+        let points = self.scoring.record_clear(removed.len() as u32);
+        if !removed.is_empty() {
+            self.cleared_groups.push(ClearedGroup {
+                blocks: removed,
+                points,
+            });
+        }
+        true
+    }
+
+    /// Takes the groups cleared since the last call, for their clear effects
+    pub fn take_cleared_groups(&mut self) -> Vec<ClearedGroup> {
+        std::mem::take(&mut self.cleared_groups)
+    }
+
+    /// The block drawn at `point`, as the cell it is stored in and the cell it ends up in
+    /// once the moves queued so far play out
+    pub fn block_under(&self, point: Vec2) -> Option<(Coordinate, Coordinate)> {
+        if let Some(moving) = self.physics_system.block_at(&self.layout, point) {
+            return Some(moving);
+        }
+
+        let position = self.layout.world_to_grid(point)?;
+        // A cell a block is moving out of looks empty
+        (self.layout.top_layer(position).is_some() && !self.physics_system.is_moving_from(position))
+            .then_some((position, position))
+    }
+
+    /// Whether blocks are still moving or being cleared, so a click would have to wait
+    pub fn is_busy(&self) -> bool {
+        !matches!(self.state, GameState::Playing) || self.queued_click.is_some()
+    }
+
+    /// Forgets the queued click if it was on one of `positions`, whose blocks have been
+    /// cleared or changed since it was made
+    fn drop_click_on(&mut self, positions: &[Coordinate]) {
+        if self
+            .queued_click
+            .is_some_and(|click| positions.contains(&click))
+        {
+            self.queued_click = None;
+        }
+    }
+
+    /// Called after every move that cleared blocks
+    fn record_move(&mut self) {
+        self.chain = 0;
+        self.moves_since_haunting += 1;
+    }
+
+    /// Changes the type of every haunted block if enough moves have passed. Returns true if
+    /// any block changed. Must only be called once the board has settled.
+    pub fn haunt_if_due(&mut self) -> bool {
+        if !self.rules.haunted || self.moves_since_haunting < MOVES_BETWEEN_HAUNTINGS {
+            return false;
+        }
+
+        self.moves_since_haunting = 0;
+        let changed = self.layout.haunt(&self.rng);
+        self.drop_click_on(&changed);
+        // Transformed blocks can complete a group just like moved ones
+        self.moved_blocks.extend(changed.iter().copied());
+        !changed.is_empty()
+    }
+
+    /// Must only be called once the board has settled
     pub fn is_game_over(&self) -> bool {
         self.layout.blocks_remaining == 0
+            || !self
+                .layout
+                .has_region_of_size(self.rules.mode.min_group_size())
+            || self.level.as_ref().is_some_and(|level| {
+                level.goal.ends_when_met() && level.goal.is_met(&self.layout, self.score())
+            })
+    }
+
+    /// Stars earned on the level being played. Must only be called once the game is over.
+    pub fn level_stars(&self) -> Option<u8> {
+        self.level.as_ref().map(|level| {
+            let score = self.score();
+            level.stars_for(level.goal.is_met(&self.layout, score), score)
+        })
+    }
+
+    /// Reveals the whole board if only hidden blocks are left to play. Returns true if
+    /// anything was revealed. Must only be called once the board has settled.
+    pub fn reveal_if_stuck(&mut self) -> bool {
+        self.layout.blocks_remaining > 0
+            && !self
+                .layout
+                .has_region_of_size(self.rules.mode.min_group_size())
+            && self.layout.reveal_all()
     }
 
     pub fn blocks_remaining(&self) -> u32 {
         self.layout.blocks_remaining
     }
+
+    pub fn score(&self) -> u32 {
+        self.scoring.score()
+    }
+
+    /// The group at `position`, if it is large enough to be cleared under the current mode
+    pub fn clearable_region(&self, position: Coordinate) -> HashSet<Coordinate> {
+        let region = self.layout.get_block_region(position);
+        if region.len() as u32 >= self.rules.mode.min_group_size() {
+            region
+        } else {
+            HashSet::new()
+        }
+    }
+
+    /// Animates each block from its current cell to its destination, revealing hidden
+    /// blocks that it falls past
+    pub fn queue_moves(&mut self, moves: Vec<BlockMove>) {
+        for block_move in &moves {
+            let landing = block_move.via.unwrap_or(block_move.to);
+            self.layout.reveal_along_fall(block_move.from, landing);
+            self.moved_blocks.insert(block_move.to);
+        }
+        if let Some(click) = &mut self.queued_click
+            && let Some(block_move) = moves.iter().find(|block_move| block_move.from == *click)
+        {
+            *click = block_move.to;
+        }
+        self.physics_system.queue_block_animations(&moves);
+    }
+
+    /// Finds the groups newly formed by blocks that moved since the last check. Returns
+    /// true and queues them as the next cascade step if there are any.
+    pub fn start_cascade(&mut self) -> bool {
+        let moved_blocks = std::mem::take(&mut self.moved_blocks);
+        let Some(min_size) = self.rules.mode.cascade_group_size() else {
+            return false;
+        };
+
+        let regions = self.layout.find_regions_touching(&moved_blocks, min_size);
+        if regions.is_empty() {
+            return false;
+        }
+
+        self.chain += 1;
+        self.pending_cascade = regions.into_iter().flatten().collect();
+        true
+    }
+
+    /// Clears the groups queued by `start_cascade`, scoring each one at the current chain step
+    pub fn clear_cascade(&mut self) {
+        let pending = std::mem::take(&mut self.pending_cascade);
+        let min_size = self.rules.mode.cascade_group_size().unwrap_or(1);
+        for region in self.layout.find_regions_touching(&pending, min_size) {
+            let positions = region.into_iter().collect::<Vec<_>>();
+            let removed = self.layout.remove_blocks(&positions);
+            self.drop_click_on(&positions);
+            let points = self
+                .scoring
+                .record_chain_clear(removed.len() as u32, self.chain);
+            self.cleared_groups.push(ClearedGroup {
+                blocks: removed,
+                points,
+            });
+        }
+    }
 }
@@ -0,0 +1,21 @@
+use crate::{
+    clear_effect::ClearEffect, difficulty::Difficulty, easing::Easing, gravity::Gravity,
+    grid_size::GridSize, orientation::Orientation, rules::GameRules, settle_mode::SettleMode,
+};
+
+/// Options chosen on the settings screen, applied to the next new game
+#[derive(Copy, Clone, Default)]
+pub struct GameSettings {
+    pub grid_size: GridSize,
+    pub difficulty: Difficulty,
+    pub orientation: Orientation,
+    /// Like orientation, gravity mirrors the board rather than changing the rules, so it is
+    /// not part of the high score bucket
+    pub gravity: Gravity,
+    /// How blocks move when they fall or shift, which is only for show
+    pub easing: Easing,
+    pub landing_bounce: bool,
+    pub settle_mode: SettleMode,
+    pub clear_effect: ClearEffect,
+    pub rules: GameRules,
+}
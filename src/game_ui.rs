@@ -1,25 +1,29 @@
 use macroquad::{
+    input::{
+        KeyCode, MouseButton, is_key_pressed, is_mouse_button_down, mouse_position, mouse_wheel,
+    },
     miniquad::window::set_mouse_cursor,
     text::{Font, load_ttf_font_from_bytes},
+    window::screen_height,
 };
 
 use crate::{
-    app::{AppState, UiContext},
-    difficulty::Difficulty,
-    grid_size::GridSize,
-    high_scores::HighScores,
-    orientation::Orientation,
+    app::{AppState, LayoutContext, UiContext},
+    constants::ui::{SCROLL_STEP, WINDOW_PADDING},
 };
 
 mod buttons;
 mod layout;
+mod screen;
 
-pub use buttons::ButtonId;
+pub use buttons::{ButtonId, SliderId};
 
 pub use layout::compute_status_panel_height;
 use layout::{
-    GameOverLayout, HighScoresLayout, MainMenuLayout, PlayingLayout, ScreenLayout, SettingsLayout,
+    ConfirmLayout, EditorLayout, EditorSetupLayout, GameOverLayout, HighScoresLayout,
+    LevelSelectLayout, MainMenuLayout, PauseLayout, PlayingLayout, SettingsLayout,
 };
+use screen::Screen;
 
 #[derive(Copy, Clone)]
 struct Fonts<'a> {
@@ -30,7 +34,11 @@ struct Fonts<'a> {
 pub struct GameUi {
     title_font: Font,
     body_font: Font,
-    screen: ScreenLayout,
+    screen: Box<dyn Screen>,
+    screen_state: AppState,
+    scroll: f32,
+    /// Slider being dragged, which follows the mouse until the button is released
+    dragging: Option<SliderId>,
 }
 
 impl GameUi {
@@ -43,7 +51,10 @@ impl GameUi {
         Self {
             title_font,
             body_font,
-            screen: ScreenLayout::default(),
+            screen: Box::new(MainMenuLayout { buttons: vec![] }),
+            screen_state: AppState::MainMenu,
+            scroll: 0.0,
+            dragging: None,
         }
     }
 
@@ -55,8 +66,11 @@ impl GameUi {
         &self.body_font
     }
 
+    /// Height of the panel below the board. Must only be called on a screen with a board.
     pub fn status_panel_height(&self) -> f32 {
-        self.screen.status_panel_height()
+        self.screen
+            .status_panel_height()
+            .expect("status_panel_height() must only be called in Playing or Editor state")
     }
 
     pub fn render(&self, ctx: UiContext) {
@@ -66,53 +80,98 @@ impl GameUi {
             title: &self.title_font,
             body: &self.body_font,
         };
-        self.screen.render(fonts, ctx.blocks_remaining, ctx.score);
+        self.screen.render(fonts, &ctx);
 
         for button in self.screen.buttons() {
-            button.render(fonts);
+            button.render(fonts, ctx.sprite_sheet);
         }
     }
 
-    pub fn handle_input(&self) -> Option<ButtonId> {
-        self.screen
+    pub fn handle_input(&mut self) -> Option<ButtonId> {
+        if is_key_pressed(KeyCode::Escape)
+            && let Some(action) = self.screen.escape_action()
+        {
+            return Some(action);
+        }
+
+        let (_, wheel_y) = mouse_wheel();
+        if wheel_y != 0.0 {
+            self.scroll_to(self.scroll - wheel_y.signum() * SCROLL_STEP);
+        }
+
+        if !is_mouse_button_down(MouseButton::Left) {
+            self.dragging = None;
+        }
+
+        let pressed = self
+            .screen
             .buttons()
             .iter()
-            .find(|button| button.is_pressed())
-            .map(|button| button.id.clone())
+            .find(|button| button.is_pressed());
+        if let Some(ButtonId::Slide(slider, _)) = pressed.map(|button| &button.id) {
+            self.dragging = Some(*slider);
+        }
+        if let Some(dragging) = self.dragging {
+            let (mouse_x, _) = mouse_position();
+            return self
+                .screen
+                .buttons()
+                .iter()
+                .filter(
+                    |button| matches!(button.id, ButtonId::Slide(slider, _) if slider == dragging),
+                )
+                .find_map(|button| button.slide_to(mouse_x));
+        }
+
+        pressed.map(|button| button.id.clone())
     }
 
-    pub fn update_buttons(
-        &mut self,
-        app_state: AppState,
-        is_existing_game: bool,
-        grid_size: GridSize,
-        difficulty: Difficulty,
-        orientation: Orientation,
-        high_scores: &HighScores,
-    ) {
-        self.screen = match app_state {
-            AppState::Playing => {
-                ScreenLayout::Playing(PlayingLayout::compute(&self.title_font, &self.body_font))
-            }
-            AppState::GameOver => {
-                ScreenLayout::GameOver(GameOverLayout::compute(&self.title_font, &self.body_font))
-            }
-            AppState::MainMenu => {
-                ScreenLayout::MainMenu(MainMenuLayout::compute(&self.title_font, is_existing_game))
-            }
-            AppState::Settings => ScreenLayout::Settings(SettingsLayout::compute(
-                &self.title_font,
-                &self.body_font,
-                grid_size,
-                difficulty,
-                orientation,
-            )),
-            AppState::HighScores => ScreenLayout::HighScores(HighScoresLayout::compute(
-                &self.title_font,
-                &self.body_font,
-                difficulty,
-                high_scores,
-            )),
+    /// Scrolls screens taller than the window, keeping their content within view
+    fn scroll_to(&mut self, scroll: f32) {
+        let max_scroll = (self.screen.content_bottom() + self.scroll + WINDOW_PADDING.y
+            - screen_height())
+        .max(0.0);
+        let scroll = scroll.clamp(0.0, max_scroll);
+        self.screen.translate(self.scroll - scroll);
+        self.scroll = scroll;
+    }
+
+    pub fn update_buttons(&mut self, app_state: AppState, ctx: LayoutContext) {
+        let fonts = Fonts {
+            title: &self.title_font,
+            body: &self.body_font,
+        };
+        self.screen = screen_builder(app_state)(fonts, &ctx);
+
+        // Freshly computed layouts start unscrolled; keep the scroll position while
+        // the same screen is being refreshed
+        let scroll = if app_state == self.screen_state {
+            self.scroll
+        } else {
+            0.0
         };
+        self.screen_state = app_state;
+        self.scroll = 0.0;
+        self.scroll_to(scroll);
+    }
+}
+
+fn build<S: Screen + 'static>(fonts: Fonts, ctx: &LayoutContext) -> Box<dyn Screen> {
+    Box::new(S::build(fonts, ctx))
+}
+
+/// The screen shown for each app state
+fn screen_builder(state: AppState) -> fn(Fonts, &LayoutContext) -> Box<dyn Screen> {
+    match state {
+        AppState::Playing => build::<PlayingLayout>,
+        AppState::Paused => build::<PauseLayout>,
+        AppState::GameOver => build::<GameOverLayout>,
+        AppState::MainMenu => build::<MainMenuLayout>,
+        AppState::Settings => build::<SettingsLayout>,
+        AppState::HighScores => build::<HighScoresLayout>,
+        AppState::LevelSelect => build::<LevelSelectLayout>,
+        AppState::Editor => build::<EditorLayout>,
+        AppState::EditorSetup => build::<EditorSetupLayout>,
+        AppState::Confirm(_) => build::<ConfirmLayout>,
     }
 }
@@ -1,37 +1,99 @@
 use macroquad::{
+    color::WHITE,
     input::{MouseButton, is_mouse_button_pressed, mouse_position},
-    math::Rect,
+    math::{Rect, vec2},
     miniquad::window::set_mouse_cursor,
+    shapes::{draw_circle, draw_rectangle},
     text::{TextDimensions, TextParams, draw_text_ex},
 };
 
 use crate::{
+    block::BlockType,
+    board_depth::BoardDepth,
+    board_shape::BoardShape,
+    board_style::BoardStyle,
+    clear_effect::ClearEffect,
+    confirmation::Confirmation,
+    connectivity::Connectivity,
     constants::{
         style::BLOCK_INSET,
         ui::{
-            BUTTON_BACKGROUND_COLOR, BUTTON_SHADOW_COLOR, CARD_BORDER_COLOR, CORNER_RADIUS,
-            LABEL_TEXT_SIZE, PRIMARY_BUTTON_COLOR, PRIMARY_BUTTON_HOVER_COLOR,
+            BUTTON_BACKGROUND_COLOR, BUTTON_PADDING, BUTTON_SHADOW_COLOR, CARD_BORDER_COLOR,
+            CORNER_RADIUS, LABEL_TEXT_SIZE, PRIMARY_BUTTON_COLOR, PRIMARY_BUTTON_HOVER_COLOR,
             PRIMARY_BUTTON_SHADOW_COLOR, TEXT_COLOR,
         },
     },
     difficulty::Difficulty,
     drawing::draw_rounded_rect,
+    easing::Easing,
+    editor_tool::EditorTool,
+    game_mode::GameMode,
+    gravity::Gravity,
     grid_size::GridSize,
+    level_goal::LevelGoal,
+    level_setting::LevelSetting,
     orientation::Orientation,
+    rules::GameRules,
+    scoring::ScoringRule,
+    settle_mode::SettleMode,
+    sprite_sheet::SpriteSheet,
 };
 
+/// Share of a slider's width given to its label, left of the track
+const SLIDER_LABEL_FRACTION: f32 = 0.4;
+
 #[derive(PartialEq, Clone)]
 pub enum ButtonId {
     Menu,
     NewGame,
     Pause,
     Resume,
+    /// Starts the current game over from its first move
+    Restart,
+    /// Goes ahead with an action after being asked to confirm it
+    Confirm(Confirmation),
     Settings,
     HighScores,
+    Levels,
+    PlayLevel(usize),
+    PlayCustomLevel(usize),
+    Editor,
+    LevelSetup,
+    TestLevel,
+    SaveLevel,
+    Undo,
+    SetBrush(BlockType),
+    SetEditorTool(EditorTool),
+    SetLevelRules(GameRules),
+    SetLevelGoal(LevelGoal),
+    AdjustLevel(LevelSetting, i32),
     Back,
     SetGridSize(GridSize),
     SetDifficulty(Difficulty),
     SetOrientation(Orientation),
+    SetGameMode(GameMode),
+    SetScoringRule(ScoringRule),
+    SetConnectivity(Connectivity),
+    SetGravity(Gravity),
+    SetBoardShape(BoardShape),
+    SetBoardDepth(BoardDepth),
+    SetHaunted(bool),
+    SetGuaranteedClear(bool),
+    SetBoardStyle(BoardStyle),
+    SetEasing(Easing),
+    SetLandingBounce(bool),
+    SetSettleMode(SettleMode),
+    SetClearEffect(ClearEffect),
+    /// A slider moved to a value
+    Slide(SliderId, u32),
+}
+
+/// A setting picked by dragging a slider
+#[derive(Copy, Clone, PartialEq)]
+pub enum SliderId {
+    GridRows,
+    GridCols,
+    Colors,
 }
 
 #[derive(Clone)]
@@ -43,6 +105,17 @@ pub enum ButtonStyle {
         sub_label: Option<String>,
         sub_label_dimensions: Option<TextDimensions>,
     },
+    /// Shows a block's sprite instead of a label
+    Swatch {
+        block_type: BlockType,
+        is_selected: bool,
+    },
+    /// A track with a knob that is dragged to pick a whole number from `min` to `max`. The
+    /// current value is carried by the button's `ButtonId::Slide`.
+    Slider {
+        min: u32,
+        max: u32,
+    },
 }
 
 pub struct Button {
@@ -81,7 +154,30 @@ impl Button {
         is_mouse_button_pressed(MouseButton::Left) && self.is_hovered()
     }
 
-    pub fn render(&self, fonts: super::Fonts) {
+    /// The left and right ends of a slider's track, which fills the right of the button
+    fn slider_track(&self) -> (f32, f32) {
+        (
+            self.bounds.x + self.bounds.w * SLIDER_LABEL_FRACTION,
+            self.bounds.right() - BUTTON_PADDING.x,
+        )
+    }
+
+    /// Where a slider moves with the mouse at `x`, or `None` if this isn't a slider or its
+    /// value would stay the same
+    pub fn slide_to(&self, x: f32) -> Option<ButtonId> {
+        let (ButtonId::Slide(slider, value), ButtonStyle::Slider { min, max }) =
+            (&self.id, &self.style)
+        else {
+            return None;
+        };
+
+        let (start, end) = self.slider_track();
+        let t = ((x - start) / (end - start)).clamp(0.0, 1.0);
+        let new_value = min + (t * (max - min) as f32).round() as u32;
+        (new_value != *value).then_some(ButtonId::Slide(*slider, new_value))
+    }
+
+    pub fn render(&self, fonts: super::Fonts, sprite_sheet: &SpriteSheet) {
         let (border_color, fill_color, hover_color, shadow_color) = match &self.style {
             ButtonStyle::Primary => (
                 PRIMARY_BUTTON_COLOR,
@@ -91,6 +187,9 @@ impl Button {
             ),
             ButtonStyle::Toggle {
                 is_selected: true, ..
+            }
+            | ButtonStyle::Swatch {
+                is_selected: true, ..
             } => (
                 PRIMARY_BUTTON_COLOR,
                 PRIMARY_BUTTON_COLOR,
@@ -141,7 +240,55 @@ impl Button {
 
         let center_x = self.bounds.center().x;
 
-        if let ButtonStyle::Toggle {
+        if let ButtonStyle::Swatch { block_type, .. } = &self.style {
+            let size = (self.bounds.w.min(face_h) - 2.0 * BUTTON_PADDING.y).max(0.0);
+            sprite_sheet.render_sprite(
+                block_type.get_sprite_id(),
+                vec2(center_x - size / 2.0, self.bounds.y + (face_h - size) / 2.0),
+                size,
+                WHITE,
+            );
+        } else if let (ButtonId::Slide(_, value), ButtonStyle::Slider { min, max }) =
+            (&self.id, &self.style)
+        {
+            let face_center_y = self.bounds.y + face_h / 2.0;
+            draw_text_ex(
+                &self.label,
+                self.bounds.x + BUTTON_PADDING.x,
+                face_center_y - self.label_dimensions.height / 2.0 + self.label_dimensions.offset_y,
+                TextParams {
+                    font_size: self.font_size,
+                    color: TEXT_COLOR,
+                    font: Some(fonts.title),
+                    ..Default::default()
+                },
+            );
+
+            let (start, end) = self.slider_track();
+            let t = (value - min) as f32 / (max - min).max(1) as f32;
+            let knob_x = start + t * (end - start);
+            let track_h = 4.0;
+            draw_rectangle(
+                start,
+                face_center_y - track_h / 2.0,
+                end - start,
+                track_h,
+                CARD_BORDER_COLOR,
+            );
+            draw_rectangle(
+                start,
+                face_center_y - track_h / 2.0,
+                knob_x - start,
+                track_h,
+                PRIMARY_BUTTON_COLOR,
+            );
+            draw_circle(
+                knob_x,
+                face_center_y,
+                face_h / 4.0,
+                PRIMARY_BUTTON_HOVER_COLOR,
+            );
+        } else if let ButtonStyle::Toggle {
             sub_label: Some(sub_label),
             sub_label_dimensions: Some(sub_dims),
             ..
@@ -1,69 +1,72 @@
+mod confirm;
+mod editor;
+mod editor_setup;
 mod game_over;
 mod high_scores;
+mod level_select;
 mod main_menu;
+mod modal;
+mod pause;
 mod playing;
 mod settings;
 
 use macroquad::{
     math::Rect,
-    text::{Font, TextDimensions, measure_text},
+    text::{Font, TextDimensions, TextParams, draw_text_ex, measure_text},
     window::screen_width,
 };
 
 use crate::constants::{
-    style::BLOCK_INSET,
-    ui::{BODY_TEXT_SIZE, BUTTON_PADDING},
+    style::{BACKGROUND_COLOR, BLOCK_INSET},
+    ui::{
+        BODY_TEXT_SIZE, BUTTON_PADDING, CARD_BORDER_COLOR, CORNER_RADIUS, LABEL_TEXT_COLOR,
+        LABEL_TEXT_SIZE, MODAL_SHADOW_COLOR, WINDOW_PADDING,
+    },
 };
+use crate::drawing::draw_rounded_rect;
 
 use super::Fonts;
-use super::buttons::{Button, ButtonId, ButtonStyle};
+use super::buttons::{Button, ButtonId, ButtonStyle, SliderId};
 
+pub use confirm::ConfirmLayout;
+pub use editor::EditorLayout;
+pub use editor_setup::EditorSetupLayout;
 pub use game_over::GameOverLayout;
 pub use high_scores::HighScoresLayout;
+pub use level_select::{LevelSelectLayout, MAX_STARS};
 pub use main_menu::MainMenuLayout;
+pub use modal::Modal;
+pub use pause::PauseLayout;
 pub use playing::{PlayingLayout, compute_status_panel_height};
 pub use settings::SettingsLayout;
 
-pub enum ScreenLayout {
-    Playing(PlayingLayout),
-    MainMenu(MainMenuLayout),
-    GameOver(GameOverLayout),
-    Settings(SettingsLayout),
-    HighScores(HighScoresLayout),
-}
+/// Draws the shadowed card behind a modal
+pub fn render_modal_card(r: &Rect) {
+    let corner = CORNER_RADIUS * 2.0;
 
-impl Default for ScreenLayout {
-    fn default() -> Self {
-        ScreenLayout::MainMenu(MainMenuLayout { buttons: vec![] })
-    }
-}
-
-impl ScreenLayout {
-    pub fn buttons(&self) -> &[Button] {
-        match self {
-            ScreenLayout::Playing(l) => &l.buttons,
-            ScreenLayout::MainMenu(l) => &l.buttons,
-            ScreenLayout::GameOver(l) => &l.buttons,
-            ScreenLayout::Settings(l) => &l.buttons,
-            ScreenLayout::HighScores(l) => &l.buttons,
-        }
-    }
+    // Drop shadow
+    draw_rounded_rect(r.x + 2.0, r.y + 6.0, r.w, r.h, corner, MODAL_SHADOW_COLOR);
 
-    pub fn render(&self, fonts: Fonts, blocks_remaining: u32, score: u32) {
-        match self {
-            ScreenLayout::Playing(l) => l.render(fonts, blocks_remaining, score),
-            ScreenLayout::GameOver(l) => l.render(fonts, score),
-            ScreenLayout::MainMenu(l) => l.render(fonts),
-            ScreenLayout::Settings(l) => l.render(fonts),
-            ScreenLayout::HighScores(l) => l.render(fonts),
-        }
-    }
+    // Card background + border
+    draw_rounded_rect(r.x, r.y, r.w, r.h, corner, CARD_BORDER_COLOR);
+    draw_rounded_rect(
+        r.x + 1.0,
+        r.y + 1.0,
+        r.w - 2.0,
+        r.h - 2.0,
+        corner - 1.0,
+        BACKGROUND_COLOR,
+    );
+}
 
-    pub fn status_panel_height(&self) -> f32 {
-        match self {
-            ScreenLayout::Playing(l) => l.status_panel_height,
-            _ => unreachable!("status_panel_height() must only be called in Playing state"),
-        }
+/// Formats seconds of play as minutes and seconds, with hours once a game runs that long
+pub fn format_game_time(seconds: f32) -> String {
+    let seconds = seconds as u32;
+    let (hours, minutes, seconds) = (seconds / 3600, seconds / 60 % 60, seconds % 60);
+    if hours > 0 {
+        format!("{hours}:{minutes:02}:{seconds:02}")
+    } else {
+        format!("{minutes}:{seconds:02}")
     }
 }
 
@@ -108,3 +111,193 @@ pub fn compute_button_stack(
 
     buttons
 }
+
+/// Lays buttons out side by side, centered across the screen, each as wide as the widest
+pub fn compute_button_row(
+    title_font: &Font,
+    items: &[(&str, ButtonId, ButtonStyle)],
+    baseline_y: f32,
+    spacing: f32,
+) -> Vec<Button> {
+    let measurements: Vec<TextDimensions> = items
+        .iter()
+        .map(|(text, _, _)| measure_text(text, Some(title_font), BODY_TEXT_SIZE, 1.0))
+        .collect();
+
+    let max_btn_w = measurements
+        .iter()
+        .map(|d| d.width + 2.0 * BUTTON_PADDING.x)
+        .fold(0.0f32, f32::max);
+    let max_offset_y = measurements
+        .iter()
+        .map(|d| d.offset_y)
+        .fold(0.0f32, f32::max);
+    let max_height = measurements.iter().map(|d| d.height).fold(0.0f32, f32::max);
+
+    let row_w = items.len() as f32 * (max_btn_w + spacing) - spacing;
+    let mut x = (screen_width() - row_w) / 2.0;
+    let mut buttons = Vec::with_capacity(items.len());
+
+    for ((text, id, style), dims) in items.iter().zip(measurements.iter()) {
+        let bounds = Rect::new(
+            x,
+            baseline_y - max_offset_y - BUTTON_PADDING.y,
+            max_btn_w,
+            max_height + 2.0 * BUTTON_PADDING.y + BLOCK_INSET,
+        );
+        buttons.push(Button::new(
+            id.clone(),
+            bounds,
+            text.to_string(),
+            *dims,
+            BODY_TEXT_SIZE,
+            style.clone(),
+        ));
+        x += max_btn_w + spacing;
+    }
+
+    buttons
+}
+
+pub struct SectionLabel {
+    pub text: String,
+    pub y: f32,
+}
+
+pub struct ToggleItem {
+    pub id: ButtonId,
+    pub label: String,
+    pub sub_label: Option<String>,
+    pub is_selected: bool,
+}
+
+/// Stacks labelled rows of toggle buttons down the screen
+pub struct ToggleSections<'a> {
+    title_font: &'a Font,
+    body_font: &'a Font,
+    pub buttons: Vec<Button>,
+    pub labels: Vec<SectionLabel>,
+    pub current_y: f32,
+}
+
+impl<'a> ToggleSections<'a> {
+    pub fn new(title_font: &'a Font, body_font: &'a Font, start_y: f32) -> Self {
+        Self {
+            title_font,
+            body_font,
+            buttons: Vec::new(),
+            labels: Vec::new(),
+            current_y: start_y,
+        }
+    }
+
+    /// Adds a section label followed by `items`, laid out `per_row` to a row
+    pub fn add(&mut self, label: &str, per_row: usize, items: Vec<ToggleItem>) {
+        if !self.labels.is_empty() {
+            self.current_y += 20.0;
+        }
+
+        let label_dims = measure_text("A", Some(self.body_font), LABEL_TEXT_SIZE, 1.0);
+        self.labels.push(SectionLabel {
+            text: label.to_uppercase(),
+            y: self.current_y,
+        });
+        self.current_y += label_dims.height + 8.0;
+        self.add_items(per_row, items);
+    }
+
+    /// Adds `items` below the previous row without a section label
+    pub fn add_row(&mut self, per_row: usize, items: Vec<ToggleItem>) {
+        if !self.buttons.is_empty() {
+            self.current_y += WINDOW_PADDING.x;
+        }
+        self.add_items(per_row, items);
+    }
+
+    /// Adds a full-width slider below the previous row for picking a value from `min` to `max`
+    pub fn add_slider(&mut self, label: &str, slider: SliderId, value: u32, min: u32, max: u32) {
+        if !self.buttons.is_empty() {
+            self.current_y += WINDOW_PADDING.x;
+        }
+
+        let label = format!("{label} · {value}");
+        let label_dims = measure_text(&label, Some(self.title_font), BODY_TEXT_SIZE, 1.0);
+        let btn_h = BUTTON_PADDING.y + label_dims.height + BUTTON_PADDING.y + BLOCK_INSET;
+        self.buttons.push(Button::new(
+            ButtonId::Slide(slider, value),
+            Rect::new(
+                WINDOW_PADDING.x,
+                self.current_y,
+                screen_width() - 2.0 * WINDOW_PADDING.x,
+                btn_h,
+            ),
+            label,
+            label_dims,
+            BODY_TEXT_SIZE,
+            ButtonStyle::Slider { min, max },
+        ));
+        self.current_y += btn_h;
+    }
+
+    fn add_items(&mut self, per_row: usize, items: Vec<ToggleItem>) {
+        let available_w = screen_width() - 2.0 * WINDOW_PADDING.x;
+        let btn_gap = WINDOW_PADDING.x;
+        let btn_w = (available_w - (per_row - 1) as f32 * btn_gap) / per_row as f32;
+
+        let main_h = items
+            .iter()
+            .map(|item| measure_text(&item.label, Some(self.title_font), BODY_TEXT_SIZE, 1.0))
+            .map(|dims| dims.height)
+            .fold(0.0f32, f32::max);
+        let sub_h = items
+            .iter()
+            .filter_map(|item| item.sub_label.as_ref())
+            .map(|sub| measure_text(sub, Some(self.body_font), LABEL_TEXT_SIZE, 1.0))
+            .map(|dims| dims.height)
+            .fold(0.0f32, f32::max);
+        let sub_block_h = if sub_h > 0.0 { 4.0 + sub_h } else { 0.0 };
+        let face_h = BUTTON_PADDING.y + main_h + sub_block_h + BUTTON_PADDING.y;
+        let btn_h = face_h + BLOCK_INSET;
+
+        let row_count = items.len().div_ceil(per_row);
+        for (i, item) in items.into_iter().enumerate() {
+            let x = WINDOW_PADDING.x + (i % per_row) as f32 * (btn_w + btn_gap);
+            let y = self.current_y + (i / per_row) as f32 * (btn_h + btn_gap);
+            let label_dims = measure_text(&item.label, Some(self.title_font), BODY_TEXT_SIZE, 1.0);
+            let sub_label_dims = item
+                .sub_label
+                .as_ref()
+                .map(|sub| measure_text(sub, Some(self.body_font), LABEL_TEXT_SIZE, 1.0));
+            self.buttons.push(Button::new(
+                item.id,
+                Rect::new(x, y, btn_w, btn_h),
+                item.label,
+                label_dims,
+                BODY_TEXT_SIZE,
+                ButtonStyle::Toggle {
+                    is_selected: item.is_selected,
+                    sub_label: item.sub_label,
+                    sub_label_dimensions: sub_label_dims,
+                },
+            ));
+        }
+        self.current_y += row_count as f32 * btn_h + row_count.saturating_sub(1) as f32 * btn_gap;
+    }
+}
+
+pub fn render_section_labels(fonts: Fonts, labels: &[SectionLabel]) {
+    let label_dims = measure_text("A", Some(fonts.body), LABEL_TEXT_SIZE, 1.0);
+    for label in labels {
+        draw_text_ex(
+            &label.text,
+            WINDOW_PADDING.x,
+            label.y + label_dims.offset_y,
+            TextParams {
+                font_size: LABEL_TEXT_SIZE,
+                color: LABEL_TEXT_COLOR,
+                font: Some(fonts.body),
+                ..Default::default()
+            },
+        );
+    }
+}
@@ -0,0 +1,57 @@
+use crate::app::{LayoutContext, UiContext};
+use crate::confirmation::Confirmation;
+
+use super::super::Fonts;
+use super::super::buttons::{Button, ButtonId, ButtonStyle};
+use super::super::screen::Screen;
+use super::Modal;
+
+/// Asks before an action that loses progress
+pub struct ConfirmLayout {
+    modal: Modal,
+}
+
+impl ConfirmLayout {
+    fn compute(fonts: Fonts, confirmation: Confirmation) -> Self {
+        let items = [
+            ("Cancel", ButtonId::Back, ButtonStyle::Secondary),
+            (
+                confirmation.action_label(),
+                ButtonId::Confirm(confirmation),
+                ButtonStyle::Primary,
+            ),
+        ];
+
+        Self {
+            modal: Modal::compute(
+                fonts,
+                confirmation.title(),
+                Some(confirmation.message()),
+                0.0,
+                &items,
+            ),
+        }
+    }
+}
+
+impl Screen for ConfirmLayout {
+    fn build(fonts: Fonts, ctx: &LayoutContext) -> Self {
+        Self::compute(
+            fonts,
+            ctx.confirmation
+                .expect("the confirm screen is only opened for a confirmation"),
+        )
+    }
+
+    fn buttons(&self) -> &[Button] {
+        &self.modal.buttons
+    }
+
+    fn escape_action(&self) -> Option<ButtonId> {
+        Some(ButtonId::Back)
+    }
+
+    fn render(&self, fonts: Fonts, _ctx: &UiContext) {
+        self.modal.render(fonts);
+    }
+}
@@ -0,0 +1,168 @@
+use macroquad::{
+    math::Rect,
+    shapes::draw_rectangle,
+    text::{Font, TextDimensions, TextParams, draw_text_ex, measure_text},
+    window::{screen_height, screen_width},
+};
+use strum::IntoEnumIterator;
+
+use crate::app::{LayoutContext, UiContext};
+use crate::{
+    block::BlockType,
+    constants::{
+        style::{BLOCK_INSET, GRID_BACKGROUND_COLOR},
+        ui::{BODY_TEXT_SIZE, LABEL_TEXT_COLOR, LABEL_TEXT_SIZE, WINDOW_PADDING},
+    },
+    editor_tool::EditorTool,
+    level_editor::LevelEditor,
+};
+
+use super::super::Fonts;
+use super::super::buttons::{Button, ButtonId, ButtonStyle};
+use super::super::screen::Screen;
+use super::{ToggleItem, ToggleSections};
+
+/// Swatches stretch to fill the width of the toolbar but stay no taller than this
+const MAX_SWATCH_HEIGHT: f32 = 48.0;
+
+/// The board being edited, with a toolbar along the bottom of the screen
+pub struct EditorLayout {
+    pub toolbar_height: f32,
+    pub buttons: Vec<Button>,
+    info_text: String,
+    info_y: f32,
+}
+
+impl EditorLayout {
+    fn compute(title_font: &Font, body_font: &Font, editor: &LevelEditor) -> Self {
+        let info_dims = measure_text("A", Some(body_font), LABEL_TEXT_SIZE, 1.0);
+        let available_w = screen_width() - 2.0 * WINDOW_PADDING.x;
+        let gap = WINDOW_PADDING.x;
+
+        // Laid out from the top of the toolbar, then moved down to the bottom of the screen
+        let mut y = WINDOW_PADDING.y + info_dims.height + 8.0;
+
+        let swatch_count = BlockType::all().count();
+        let swatch_w = (available_w - (swatch_count - 1) as f32 * gap) / swatch_count as f32;
+        let swatch_h = swatch_w.min(MAX_SWATCH_HEIGHT) + BLOCK_INSET;
+        let mut buttons: Vec<Button> = BlockType::all()
+            .enumerate()
+            .map(|(i, block_type)| {
+                Button::new(
+                    ButtonId::SetBrush(block_type.clone()),
+                    Rect::new(
+                        WINDOW_PADDING.x + i as f32 * (swatch_w + gap),
+                        y,
+                        swatch_w,
+                        swatch_h,
+                    ),
+                    String::new(),
+                    TextDimensions::default(),
+                    BODY_TEXT_SIZE,
+                    ButtonStyle::Swatch {
+                        is_selected: block_type == editor.brush,
+                        block_type,
+                    },
+                )
+            })
+            .collect();
+        y += swatch_h + gap;
+
+        let mut sections = ToggleSections::new(title_font, body_font, y);
+        let mut tools: Vec<ToggleItem> = EditorTool::iter()
+            .map(|tool| ToggleItem {
+                id: ButtonId::SetEditorTool(tool),
+                label: tool.label().to_string(),
+                sub_label: None,
+                is_selected: tool == editor.tool,
+            })
+            .collect();
+        tools.push(action("Undo", ButtonId::Undo));
+        sections.add_row(4, tools);
+        sections.add_row(
+            4,
+            vec![
+                action("Setup", ButtonId::LevelSetup),
+                action("Test", ButtonId::TestLevel),
+                action("Save", ButtonId::SaveLevel),
+                action("Back", ButtonId::Back),
+            ],
+        );
+        buttons.extend(sections.buttons);
+
+        let toolbar_height = sections.current_y + WINDOW_PADDING.y;
+        let toolbar_y = screen_height() - toolbar_height;
+        for button in &mut buttons {
+            button.bounds.y += toolbar_y;
+        }
+
+        let info_text = editor.status.clone().unwrap_or_else(|| {
+            format!(
+                "{} · {}",
+                editor.level.name,
+                editor.level.goal.description()
+            )
+        });
+
+        Self {
+            toolbar_height,
+            buttons,
+            info_text,
+            info_y: toolbar_y + WINDOW_PADDING.y,
+        }
+    }
+}
+
+impl Screen for EditorLayout {
+    fn build(fonts: Fonts, ctx: &LayoutContext) -> Self {
+        match ctx.editor {
+            Some(editor) => Self::compute(fonts.title, fonts.body, editor),
+            // The editor is always open on its own screens, but a blank one stands in
+            None => Self::compute(fonts.title, fonts.body, &LevelEditor::new()),
+        }
+    }
+
+    fn buttons(&self) -> &[Button] {
+        &self.buttons
+    }
+
+    fn escape_action(&self) -> Option<ButtonId> {
+        Some(ButtonId::Back)
+    }
+
+    fn status_panel_height(&self) -> Option<f32> {
+        Some(self.toolbar_height)
+    }
+
+    fn render(&self, fonts: Fonts, _ctx: &UiContext) {
+        draw_rectangle(
+            0.0,
+            screen_height() - self.toolbar_height,
+            screen_width(),
+            self.toolbar_height,
+            GRID_BACKGROUND_COLOR,
+        );
+
+        let dims = measure_text(&self.info_text, Some(fonts.body), LABEL_TEXT_SIZE, 1.0);
+        draw_text_ex(
+            &self.info_text,
+            WINDOW_PADDING.x,
+            self.info_y + dims.offset_y,
+            TextParams {
+                font_size: LABEL_TEXT_SIZE,
+                color: LABEL_TEXT_COLOR,
+                font: Some(fonts.body),
+                ..Default::default()
+            },
+        );
+    }
+}
+
+fn action(label: &str, id: ButtonId) -> ToggleItem {
+    ToggleItem {
+        id,
+        label: label.to_string(),
+        sub_label: None,
+        is_selected: false,
+    }
+}
@@ -0,0 +1,293 @@
+use macroquad::{
+    text::{Font, TextParams, draw_text_ex, measure_text},
+    window::screen_width,
+};
+use num_format::{Locale, ToFormattedString};
+use strum::IntoEnumIterator;
+
+use crate::app::{LayoutContext, UiContext};
+use crate::{
+    board_shape::BoardShape,
+    connectivity::Connectivity,
+    constants::{
+        editor::DEFAULT_TARGET_SCORE,
+        ui::{
+            BODY_TEXT_SIZE, BUTTON_PADDING, CHROME_HEIGHT, LABEL_TEXT_SIZE, TEXT_COLOR,
+            TITLE_TEXT_SIZE, WINDOW_PADDING,
+        },
+    },
+    game_mode::GameMode,
+    level::Level,
+    level_editor::LevelEditor,
+    level_goal::LevelGoal,
+    level_setting::LevelSetting,
+    rules::GameRules,
+    scoring::ScoringRule,
+};
+
+use super::super::Fonts;
+use super::super::buttons::{Button, ButtonId, ButtonStyle};
+use super::super::screen::Screen;
+use super::{
+    SectionLabel, ToggleItem, ToggleSections, compute_button_stack, render_section_labels,
+};
+
+/// The name, size, goal, star scores and rules of the level being edited
+pub struct EditorSetupLayout {
+    pub title_y: f32,
+    pub content_bottom: f32,
+    pub buttons: Vec<Button>,
+    labels: Vec<SectionLabel>,
+    name: String,
+    name_y: f32,
+}
+
+impl EditorSetupLayout {
+    fn compute(title_font: &Font, body_font: &Font, editor: &LevelEditor) -> Self {
+        let level = &editor.level;
+        let title_dims = measure_text("Level setup", Some(title_font), TITLE_TEXT_SIZE, 1.0);
+        let title_y = CHROME_HEIGHT + WINDOW_PADDING.y + title_dims.height;
+        let label_a_dims = measure_text("A", Some(body_font), LABEL_TEXT_SIZE, 1.0);
+        let name_dims = measure_text("A", Some(body_font), BODY_TEXT_SIZE, 1.0);
+
+        let name_label = SectionLabel {
+            text: "NAME · TYPE TO RENAME".to_string(),
+            y: title_y + 16.0,
+        };
+        let name_y = name_label.y + label_a_dims.height + 8.0;
+
+        let mut sections =
+            ToggleSections::new(title_font, body_font, name_y + name_dims.height + 20.0);
+
+        sections.add(
+            &format!("Size · {} × {}", level.rows, level.cols),
+            2,
+            vec![
+                adjust_item("Rows -", LevelSetting::Rows, -1),
+                adjust_item("Rows +", LevelSetting::Rows, 1),
+                adjust_item("Columns -", LevelSetting::Cols, -1),
+                adjust_item("Columns +", LevelSetting::Cols, 1),
+            ],
+        );
+
+        let goals = [
+            ("Clear board", LevelGoal::ClearBoard),
+            ("Reach score", LevelGoal::ReachScore(DEFAULT_TARGET_SCORE)),
+            ("Clear type", LevelGoal::ClearType(editor.brush.clone())),
+        ];
+        sections.add(
+            "Goal",
+            3,
+            goals
+                .into_iter()
+                .map(|(label, goal)| {
+                    let is_selected =
+                        std::mem::discriminant(&goal) == std::mem::discriminant(&level.goal);
+                    ToggleItem {
+                        // Reselecting the current goal keeps its target
+                        id: ButtonId::SetLevelGoal(if is_selected {
+                            level.goal.clone()
+                        } else {
+                            goal
+                        }),
+                        label: label.to_string(),
+                        sub_label: None,
+                        is_selected,
+                    }
+                })
+                .collect(),
+        );
+        if let LevelGoal::ReachScore(target) = level.goal {
+            sections.add(
+                &format!("Target score · {}", target.to_formatted_string(&Locale::en)),
+                2,
+                vec![
+                    adjust_item("-", LevelSetting::TargetScore, -1),
+                    adjust_item("+", LevelSetting::TargetScore, 1),
+                ],
+            );
+        }
+
+        sections.add(
+            &format!(
+                "Star scores · {} / {}",
+                level.star_scores[0].to_formatted_string(&Locale::en),
+                level.star_scores[1].to_formatted_string(&Locale::en)
+            ),
+            4,
+            vec![
+                adjust_item("2nd -", LevelSetting::SecondStar, -1),
+                adjust_item("2nd +", LevelSetting::SecondStar, 1),
+                adjust_item("3rd -", LevelSetting::ThirdStar, -1),
+                adjust_item("3rd +", LevelSetting::ThirdStar, 1),
+            ],
+        );
+
+        let rules = level.rules;
+        // The editor shows every block as it will be placed, so modes that hide blocks
+        // can't be previewed and aren't offered
+        sections.add(
+            "Mode",
+            2,
+            GameMode::iter()
+                .filter(|mode| !mode.hides_blocks())
+                .map(|mode| rules_item(level, mode.label(), None, GameRules { mode, ..rules }))
+                .collect(),
+        );
+        sections.add(
+            "Scoring",
+            2,
+            ScoringRule::iter()
+                .map(|scoring| {
+                    rules_item(
+                        level,
+                        scoring.label(),
+                        Some(scoring.formula_hint()),
+                        GameRules { scoring, ..rules },
+                    )
+                })
+                .collect(),
+        );
+        sections.add(
+            "Board",
+            2,
+            BoardShape::iter()
+                .map(|shape| {
+                    rules_item(
+                        level,
+                        shape.label(),
+                        Some(shape.hint()),
+                        rules.with_shape(shape),
+                    )
+                })
+                .collect(),
+        );
+        let connectivity_variants = Connectivity::iter()
+            .filter(|connectivity| connectivity.applies_to(rules.shape))
+            .collect::<Vec<_>>();
+        sections.add(
+            "Connectivity",
+            connectivity_variants.len(),
+            connectivity_variants
+                .into_iter()
+                .map(|connectivity| {
+                    rules_item(
+                        level,
+                        connectivity.label(),
+                        Some(connectivity.hint()),
+                        GameRules {
+                            connectivity,
+                            ..rules
+                        },
+                    )
+                })
+                .collect(),
+        );
+
+        let current_y = sections.current_y + 24.0;
+        let done_dims = measure_text("Done", Some(title_font), BODY_TEXT_SIZE, 1.0);
+        let done_baseline = current_y + done_dims.offset_y + BUTTON_PADDING.y;
+        let mut buttons = sections.buttons;
+        buttons.extend(compute_button_stack(
+            title_font,
+            &[("Done", ButtonId::Editor, ButtonStyle::Secondary)],
+            done_baseline,
+        ));
+        let content_bottom = buttons.last().map_or(current_y, |b| b.bounds.bottom());
+
+        let mut labels = vec![name_label];
+        labels.extend(sections.labels);
+
+        Self {
+            title_y,
+            content_bottom,
+            buttons,
+            labels,
+            name: format!("{}_", level.name),
+            name_y,
+        }
+    }
+}
+
+impl Screen for EditorSetupLayout {
+    fn build(fonts: Fonts, ctx: &LayoutContext) -> Self {
+        match ctx.editor {
+            Some(editor) => Self::compute(fonts.title, fonts.body, editor),
+            // The editor is always open on its own screens, but a blank one stands in
+            None => Self::compute(fonts.title, fonts.body, &LevelEditor::new()),
+        }
+    }
+
+    fn buttons(&self) -> &[Button] {
+        &self.buttons
+    }
+
+    fn escape_action(&self) -> Option<ButtonId> {
+        Some(ButtonId::Editor)
+    }
+
+    fn content_bottom(&self) -> f32 {
+        self.content_bottom
+    }
+
+    fn translate(&mut self, dy: f32) {
+        self.title_y += dy;
+        self.content_bottom += dy;
+        self.name_y += dy;
+        for label in &mut self.labels {
+            label.y += dy;
+        }
+        for button in &mut self.buttons {
+            button.bounds.y += dy;
+        }
+    }
+
+    fn render(&self, fonts: Fonts, _ctx: &UiContext) {
+        let text = "Level setup";
+        let dims = measure_text(text, Some(fonts.title), TITLE_TEXT_SIZE, 1.0);
+        draw_text_ex(
+            text,
+            (screen_width() - dims.width) / 2.0,
+            self.title_y,
+            TextParams {
+                font_size: TITLE_TEXT_SIZE,
+                color: TEXT_COLOR,
+                font: Some(fonts.title),
+                ..Default::default()
+            },
+        );
+
+        let name_dims = measure_text("A", Some(fonts.body), BODY_TEXT_SIZE, 1.0);
+        draw_text_ex(
+            &self.name,
+            WINDOW_PADDING.x,
+            self.name_y + name_dims.offset_y,
+            TextParams {
+                font_size: BODY_TEXT_SIZE,
+                color: TEXT_COLOR,
+                font: Some(fonts.body),
+                ..Default::default()
+            },
+        );
+
+        render_section_labels(fonts, &self.labels);
+    }
+}
+
+fn adjust_item(label: &str, setting: LevelSetting, steps: i32) -> ToggleItem {
+    ToggleItem {
+        id: ButtonId::AdjustLevel(setting, steps),
+        label: label.to_string(),
+        sub_label: None,
+        is_selected: false,
+    }
+}
+
+fn rules_item(level: &Level, label: &str, hint: Option<&str>, rules: GameRules) -> ToggleItem {
+    ToggleItem {
+        id: ButtonId::SetLevelRules(rules),
+        label: label.to_string(),
+        sub_label: hint.map(str::to_string),
+        is_selected: rules == level.rules,
+    }
+}
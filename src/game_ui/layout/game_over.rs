@@ -1,96 +1,129 @@
-use macroquad::{
-    math::Rect,
-    text::{Font, TextParams, draw_text_ex, measure_text},
-    window::{screen_height, screen_width},
-};
+use macroquad::text::{TextParams, draw_text_ex, measure_text};
 use num_format::{Locale, ToFormattedString};
 
-use crate::constants::{
-    style::BACKGROUND_COLOR,
-    ui::{
-        BODY_TEXT_SIZE, BUTTON_PADDING, CARD_BORDER_COLOR, CHROME_HEIGHT, CORNER_RADIUS,
-        MODAL_PADDING, MODAL_SHADOW_COLOR, TEXT_COLOR, TITLE_TEXT_SIZE,
-    },
-};
-use crate::drawing::draw_rounded_rect;
+use crate::app::{LayoutContext, UiContext};
+use crate::constants::ui::{BODY_TEXT_SIZE, LABEL_TEXT_COLOR, MODAL_PADDING, TEXT_COLOR};
 
 use super::super::Fonts;
 use super::super::buttons::{Button, ButtonId, ButtonStyle};
-use super::compute_button_stack;
+use super::super::screen::Screen;
+use super::{MAX_STARS, Modal};
+
+/// Clear points, then either the board clear bonus or the remaining block penalty
+const BREAKDOWN_ROWS: usize = 2;
 
 pub struct GameOverLayout {
-    pub buttons: Vec<Button>,
-    pub modal_rect: Rect,
+    modal: Modal,
+    /// The rating earned, if the game was a level
+    level_stars: Option<u8>,
 }
 
 impl GameOverLayout {
-    pub fn compute(title_font: &Font, body_font: &Font) -> Self {
-        let screen_w = screen_width();
-        let screen_h = screen_height();
-
-        let title_dims = measure_text("Game Over!", Some(title_font), TITLE_TEXT_SIZE, 1.0);
-        let score_dims = measure_text("Score: 000,000", Some(body_font), BODY_TEXT_SIZE, 1.0);
-        let btn_label_dims = measure_text("Menu", Some(title_font), BODY_TEXT_SIZE, 1.0);
-        let btn_h = btn_label_dims.height + 2.0 * BUTTON_PADDING.y + 2.0; // face_h + BLOCK_INSET
+    /// Games testing a level from the editor return to the editor instead of the menu. Levels
+    /// are titled by whether they were passed.
+    fn compute(fonts: Fonts, is_test_play: bool, level_stars: Option<u8>) -> Self {
+        let title = match level_stars {
+            None => "Game Over!",
+            Some(0) => "Level Failed",
+            Some(_) => "Level Complete!",
+        };
+        let score_dims = measure_text("Score: 000,000", Some(fonts.body), BODY_TEXT_SIZE, 1.0);
+        let row_dims = measure_text("Clears", Some(fonts.body), BODY_TEXT_SIZE, 1.0);
+        let breakdown_h = BREAKDOWN_ROWS as f32 * (row_dims.height + 6.0);
+        let content_h = breakdown_h + 8.0 + score_dims.height;
 
-        let content_h = title_dims.height + 12.0 + score_dims.height + 20.0 + btn_h + MODAL_PADDING;
-        let modal_h = content_h + MODAL_PADDING * 2.0;
-        let modal_w = (screen_w * 0.6).max(280.0).min(400.0);
-        let modal_x = (screen_w - modal_w) / 2.0;
-        let modal_y = CHROME_HEIGHT + (screen_h - CHROME_HEIGHT - modal_h) / 2.0;
-
-        let btn_anchor_y = modal_y + modal_h - MODAL_PADDING;
-        let buttons = compute_button_stack(
-            title_font,
-            &[("Menu", ButtonId::Menu, ButtonStyle::Secondary)],
-            btn_anchor_y,
-        );
+        let button = if is_test_play {
+            ("Edit", ButtonId::Editor, ButtonStyle::Secondary)
+        } else {
+            ("Menu", ButtonId::Menu, ButtonStyle::Secondary)
+        };
 
         Self {
-            buttons,
-            modal_rect: Rect::new(modal_x, modal_y, modal_w, modal_h),
+            modal: Modal::compute(fonts, title, None, content_h, &[button]),
+            level_stars,
         }
     }
+}
 
-    pub fn render(&self, fonts: Fonts, score: u32) {
-        let r = &self.modal_rect;
-        let corner = CORNER_RADIUS * 2.0;
+impl Screen for GameOverLayout {
+    fn build(fonts: Fonts, ctx: &LayoutContext) -> Self {
+        Self::compute(
+            fonts,
+            ctx.editor.is_some_and(|editor| editor.is_testing),
+            ctx.level_stars,
+        )
+    }
 
-        // Drop shadow
-        draw_rounded_rect(r.x + 2.0, r.y + 6.0, r.w, r.h, corner, MODAL_SHADOW_COLOR);
+    fn buttons(&self) -> &[Button] {
+        &self.modal.buttons
+    }
 
-        // Card background + border
-        draw_rounded_rect(r.x, r.y, r.w, r.h, corner, CARD_BORDER_COLOR);
-        draw_rounded_rect(
-            r.x + 1.0,
-            r.y + 1.0,
-            r.w - 2.0,
-            r.h - 2.0,
-            corner - 1.0,
-            BACKGROUND_COLOR,
-        );
+    /// Shows the rating earned too if the game was a level
+    fn render(&self, fonts: Fonts, ctx: &UiContext) {
+        let breakdown = ctx.score_breakdown;
+        self.modal.render(fonts);
 
+        let r = &self.modal.rect;
         let cx = r.x + r.w / 2.0;
-        let mut y = r.y + MODAL_PADDING;
+        let mut y = self.modal.content_y;
 
-        // Title
-        let title = "Game Over!";
-        let title_dims = measure_text(title, Some(fonts.title), TITLE_TEXT_SIZE, 1.0);
-        draw_text_ex(
-            title,
-            cx - title_dims.width / 2.0,
-            y + title_dims.offset_y,
-            TextParams {
-                font_size: TITLE_TEXT_SIZE,
-                color: TEXT_COLOR,
-                font: Some(fonts.title),
-                ..Default::default()
-            },
-        );
-        y += title_dims.height + 12.0;
+        // Breakdown
+        let adjustment = if breakdown.blocks_remaining == 0 {
+            (
+                "Board clear".to_string(),
+                format!(
+                    "+{}",
+                    breakdown.board_clear_bonus.to_formatted_string(&Locale::en)
+                ),
+            )
+        } else {
+            (
+                format!(
+                    "{} blocks left",
+                    breakdown.blocks_remaining.to_formatted_string(&Locale::en)
+                ),
+                format!(
+                    "-{}",
+                    breakdown.remaining_penalty.to_formatted_string(&Locale::en)
+                ),
+            )
+        };
+        let rows: [(String, String); BREAKDOWN_ROWS] = [
+            (
+                "Clears".to_string(),
+                breakdown.clears.to_formatted_string(&Locale::en),
+            ),
+            adjustment,
+        ];
+        let row_params = TextParams {
+            font_size: BODY_TEXT_SIZE,
+            color: LABEL_TEXT_COLOR,
+            font: Some(fonts.body),
+            ..Default::default()
+        };
+        let row_h = measure_text("Clears", Some(fonts.body), BODY_TEXT_SIZE, 1.0).height + 6.0;
+        let left_x = r.x + MODAL_PADDING;
+        let right_x = r.x + r.w - MODAL_PADDING;
+        for (label, value) in &rows {
+            let label_dims = measure_text(label, Some(fonts.body), BODY_TEXT_SIZE, 1.0);
+            let value_dims = measure_text(value, Some(fonts.body), BODY_TEXT_SIZE, 1.0);
+            draw_text_ex(label, left_x, y + label_dims.offset_y, row_params.clone());
+            draw_text_ex(
+                value,
+                right_x - value_dims.width,
+                y + label_dims.offset_y,
+                row_params.clone(),
+            );
+            y += row_h;
+        }
+        y += 8.0;
 
         // Score
-        let score_text = format!("Score: {}", score.to_formatted_string(&Locale::en));
+        let score = breakdown.total();
+        let mut score_text = format!("Score: {}", score.to_formatted_string(&Locale::en));
+        if let Some(stars) = self.level_stars {
+            score_text.push_str(&format!(" · {stars}/{MAX_STARS} stars"));
+        }
         let score_dims = measure_text(&score_text, Some(fonts.body), BODY_TEXT_SIZE, 1.0);
         draw_text_ex(
             &score_text,
@@ -1,26 +1,28 @@
 use macroquad::{
-    math::Rect,
     text::{Font, TextParams, draw_text_ex, measure_text},
     window::screen_width,
 };
 use num_format::{Locale, ToFormattedString};
 
+use crate::app::{LayoutContext, UiContext};
 use crate::{
-    constants::{
-        style::BLOCK_INSET,
-        ui::{
-            BODY_TEXT_SIZE, BUTTON_PADDING, CHROME_HEIGHT, LABEL_TEXT_COLOR, LABEL_TEXT_SIZE,
-            TEXT_COLOR, TITLE_TEXT_SIZE, WINDOW_PADDING,
-        },
+    constants::ui::{
+        BODY_TEXT_SIZE, BUTTON_PADDING, CHROME_HEIGHT, LABEL_TEXT_COLOR, LABEL_TEXT_SIZE,
+        TEXT_COLOR, TITLE_TEXT_SIZE, WINDOW_PADDING,
     },
     difficulty::Difficulty,
+    game_settings::GameSettings,
     grid_size::GridSize,
     high_scores::HighScores,
+    score_bucket::ScoreBucket,
 };
 
 use super::super::Fonts;
 use super::super::buttons::{Button, ButtonId, ButtonStyle};
-use super::compute_button_stack;
+use super::super::screen::Screen;
+use super::{
+    SectionLabel, ToggleItem, ToggleSections, compute_button_stack, render_section_labels,
+};
 
 struct GridSizeSection {
     label: String,
@@ -31,65 +33,73 @@ struct GridSizeSection {
 
 pub struct HighScoresLayout {
     pub buttons: Vec<Button>,
-    pub difficulty_label_y: f32,
+    pub title_y: f32,
     pub score_row_height: f32,
+    pub content_bottom: f32,
+    labels: Vec<SectionLabel>,
     sections: Vec<GridSizeSection>,
 }
 
 impl HighScoresLayout {
-    pub fn compute(
+    fn compute(
         title_font: &Font,
         body_font: &Font,
-        difficulty: Difficulty,
+        settings: GameSettings,
         high_scores: &HighScores,
     ) -> Self {
-        let available_w = screen_width() - 2.0 * WINDOW_PADDING.x;
-        let btn_gap = WINDOW_PADDING.x;
-
+        let GameSettings {
+            difficulty, rules, ..
+        } = settings;
         let title_dims = measure_text("High Scores", Some(title_font), TITLE_TEXT_SIZE, 1.0);
-        let mut current_y = CHROME_HEIGHT + WINDOW_PADDING.y + title_dims.height + 16.0;
+        let title_y = CHROME_HEIGHT + WINDOW_PADDING.y + title_dims.height;
+        let label_a_dims = measure_text("A", Some(body_font), LABEL_TEXT_SIZE, 1.0);
+
+        // Scores are only comparable under the same rules, so show which ones these are
+        let rules_label = SectionLabel {
+            text: rules.summary().to_uppercase(),
+            y: title_y + 16.0,
+        };
 
         // Difficulty filter row
-        let label_a_dims = measure_text("A", Some(body_font), LABEL_TEXT_SIZE, 1.0);
-        let difficulty_label_y = current_y;
-        current_y += label_a_dims.height + 8.0;
-
-        let diff_btn_w = (available_w - 2.0 * btn_gap) / 3.0;
-        let diff_main_dims = measure_text("Normal", Some(title_font), BODY_TEXT_SIZE, 1.0);
-        let diff_face_h = BUTTON_PADDING.y + diff_main_dims.height + BUTTON_PADDING.y;
-        let diff_btn_h = diff_face_h + BLOCK_INSET;
-
-        let mut buttons = Vec::new();
-        let diff_variants = [Difficulty::Easy, Difficulty::Normal, Difficulty::Hard];
-        for (i, diff) in diff_variants.iter().enumerate() {
-            let x = WINDOW_PADDING.x + i as f32 * (diff_btn_w + btn_gap);
-            let label = diff.label().to_string();
-            let label_dims = measure_text(&label, Some(title_font), BODY_TEXT_SIZE, 1.0);
-            buttons.push(Button::new(
-                ButtonId::SetDifficulty(*diff),
-                Rect::new(x, current_y, diff_btn_w, diff_btn_h),
-                label,
-                label_dims,
-                BODY_TEXT_SIZE,
-                ButtonStyle::Toggle {
+        let mut toggles = ToggleSections::new(
+            title_font,
+            body_font,
+            rules_label.y + label_a_dims.height + 16.0,
+        );
+        let mut diff_variants = Difficulty::presets();
+        diff_variants.push(difficulty.as_custom());
+        toggles.add(
+            "Difficulty",
+            diff_variants.len(),
+            diff_variants
+                .iter()
+                .map(|diff| ToggleItem {
+                    id: ButtonId::SetDifficulty(*diff),
+                    label: diff.label().to_string(),
+                    sub_label: Some(format!("{} colors", diff.block_type_count())),
                     is_selected: *diff == difficulty,
-                    sub_label: None,
-                    sub_label_dimensions: None,
-                },
-            ));
-        }
-        current_y += diff_btn_h;
+                })
+                .collect(),
+        );
+        let mut current_y = toggles.current_y;
+        let mut buttons = toggles.buttons;
+        let mut labels = vec![rules_label];
+        labels.extend(toggles.labels);
 
         // One section per grid size
         let score_row_dims = measure_text("A", Some(body_font), BODY_TEXT_SIZE, 1.0);
         let score_row_height = score_row_dims.height + 6.0;
 
-        let gs_variants = [
+        let mut gs_variants = vec![
             GridSize::Small,
             GridSize::Medium,
             GridSize::Large,
             GridSize::ExtraLarge,
         ];
+        // Custom sizes only show the one currently chosen
+        if let GridSize::Custom { .. } = settings.grid_size {
+            gs_variants.push(settings.grid_size);
+        }
         let mut sections = Vec::new();
         for gs in gs_variants {
             current_y += 20.0;
@@ -98,7 +108,7 @@ impl HighScoresLayout {
             let scores_start_y = current_y;
 
             let entries: Vec<u32> = high_scores
-                .get_scores_for(gs, difficulty)
+                .get_scores_for(ScoreBucket::new(gs, difficulty, rules))
                 .iter()
                 .map(|e| e.score)
                 .collect();
@@ -106,7 +116,13 @@ impl HighScoresLayout {
             current_y += entries.len().max(1) as f32 * score_row_height;
 
             sections.push(GridSizeSection {
-                label: gs.label().to_uppercase(),
+                label: match gs {
+                    GridSize::Custom { .. } => {
+                        format!("{} · {}", gs.label(), gs.size_hint(settings.orientation))
+                            .to_uppercase()
+                    }
+                    _ => gs.label().to_uppercase(),
+                },
                 label_y,
                 scores_start_y,
                 entries,
@@ -122,22 +138,58 @@ impl HighScoresLayout {
             &[("Back", ButtonId::Back, ButtonStyle::Secondary)],
             back_baseline,
         ));
+        let content_bottom = buttons.last().map_or(current_y, |b| b.bounds.bottom());
 
         Self {
             buttons,
-            difficulty_label_y,
+            title_y,
             score_row_height,
+            content_bottom,
+            labels,
             sections,
         }
     }
+}
+
+impl Screen for HighScoresLayout {
+    fn build(fonts: Fonts, ctx: &LayoutContext) -> Self {
+        Self::compute(fonts.title, fonts.body, ctx.settings, ctx.high_scores)
+    }
+
+    fn buttons(&self) -> &[Button] {
+        &self.buttons
+    }
+
+    fn escape_action(&self) -> Option<ButtonId> {
+        Some(ButtonId::Back)
+    }
+
+    fn content_bottom(&self) -> f32 {
+        self.content_bottom
+    }
+
+    fn translate(&mut self, dy: f32) {
+        self.title_y += dy;
+        self.content_bottom += dy;
+        for label in &mut self.labels {
+            label.y += dy;
+        }
+        for section in &mut self.sections {
+            section.label_y += dy;
+            section.scores_start_y += dy;
+        }
+        for button in &mut self.buttons {
+            button.bounds.y += dy;
+        }
+    }
 
-    pub fn render(&self, fonts: Fonts) {
+    fn render(&self, fonts: Fonts, _ctx: &UiContext) {
         let text = "High Scores";
         let dims = measure_text(text, Some(fonts.title), TITLE_TEXT_SIZE, 1.0);
         draw_text_ex(
             text,
             (screen_width() - dims.width) / 2.0,
-            CHROME_HEIGHT + WINDOW_PADDING.y + dims.height,
+            self.title_y,
             TextParams {
                 font_size: TITLE_TEXT_SIZE,
                 color: TEXT_COLOR,
@@ -146,19 +198,9 @@ impl HighScoresLayout {
             },
         );
 
-        let label_a_dims = measure_text("A", Some(fonts.body), LABEL_TEXT_SIZE, 1.0);
+        render_section_labels(fonts, &self.labels);
 
-        draw_text_ex(
-            "DIFFICULTY",
-            WINDOW_PADDING.x,
-            self.difficulty_label_y + label_a_dims.offset_y,
-            TextParams {
-                font_size: LABEL_TEXT_SIZE,
-                color: LABEL_TEXT_COLOR,
-                font: Some(fonts.body),
-                ..Default::default()
-            },
-        );
+        let label_a_dims = measure_text("A", Some(fonts.body), LABEL_TEXT_SIZE, 1.0);
 
         let score_a_dims = measure_text("A", Some(fonts.body), BODY_TEXT_SIZE, 1.0);
 
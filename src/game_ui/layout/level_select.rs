@@ -0,0 +1,175 @@
+use macroquad::{
+    text::{Font, TextParams, draw_text_ex, measure_text},
+    window::screen_width,
+};
+
+use crate::app::{LayoutContext, UiContext};
+use crate::{
+    constants::ui::{
+        BODY_TEXT_SIZE, BUTTON_PADDING, CHROME_HEIGHT, LABEL_TEXT_SIZE, TEXT_COLOR,
+        TITLE_TEXT_SIZE, WINDOW_PADDING,
+    },
+    level::Level,
+    level_progress::LevelProgress,
+};
+
+use super::super::Fonts;
+use super::super::buttons::{Button, ButtonId, ButtonStyle};
+use super::super::screen::Screen;
+use super::{
+    SectionLabel, ToggleItem, ToggleSections, compute_button_stack, render_section_labels,
+};
+
+/// Stars available on each level
+pub const MAX_STARS: u8 = 3;
+
+pub struct LevelSelectLayout {
+    pub title_y: f32,
+    pub content_bottom: f32,
+    pub buttons: Vec<Button>,
+    labels: Vec<SectionLabel>,
+}
+
+impl LevelSelectLayout {
+    fn compute(
+        title_font: &Font,
+        body_font: &Font,
+        levels: &[Level],
+        custom_levels: &[Level],
+        progress: &LevelProgress,
+    ) -> Self {
+        let title_dims = measure_text("Levels", Some(title_font), TITLE_TEXT_SIZE, 1.0);
+        let title_y = CHROME_HEIGHT + WINDOW_PADDING.y + title_dims.height;
+        let label_a_dims = measure_text("A", Some(body_font), LABEL_TEXT_SIZE, 1.0);
+
+        let earned = levels
+            .iter()
+            .chain(custom_levels)
+            .map(|level| progress.stars_for(level) as u32)
+            .sum::<u32>();
+        let progress_label = SectionLabel {
+            text: format!(
+                "{earned} / {} STARS",
+                (levels.len() + custom_levels.len()) as u32 * MAX_STARS as u32
+            ),
+            y: title_y + 16.0,
+        };
+
+        let mut sections = ToggleSections::new(
+            title_font,
+            body_font,
+            progress_label.y + label_a_dims.height + 16.0,
+        );
+        sections.add(
+            "Starter pack",
+            1,
+            levels
+                .iter()
+                .enumerate()
+                .map(|(index, level)| {
+                    level_item(ButtonId::PlayLevel(index), index, level, progress)
+                })
+                .collect(),
+        );
+        if !custom_levels.is_empty() {
+            sections.add(
+                "Custom",
+                1,
+                custom_levels
+                    .iter()
+                    .enumerate()
+                    .map(|(index, level)| {
+                        level_item(ButtonId::PlayCustomLevel(index), index, level, progress)
+                    })
+                    .collect(),
+            );
+        }
+
+        let current_y = sections.current_y + 24.0;
+        let back_dims = measure_text("Back", Some(title_font), BODY_TEXT_SIZE, 1.0);
+        let back_baseline = current_y + back_dims.offset_y + BUTTON_PADDING.y;
+        let mut buttons = sections.buttons;
+        buttons.extend(compute_button_stack(
+            title_font,
+            &[("Back", ButtonId::Back, ButtonStyle::Secondary)],
+            back_baseline,
+        ));
+        let content_bottom = buttons.last().map_or(current_y, |b| b.bounds.bottom());
+
+        let mut labels = vec![progress_label];
+        labels.extend(sections.labels);
+
+        Self {
+            title_y,
+            content_bottom,
+            buttons,
+            labels,
+        }
+    }
+}
+
+impl Screen for LevelSelectLayout {
+    fn build(fonts: Fonts, ctx: &LayoutContext) -> Self {
+        Self::compute(
+            fonts.title,
+            fonts.body,
+            ctx.levels,
+            ctx.custom_levels,
+            ctx.level_progress,
+        )
+    }
+
+    fn buttons(&self) -> &[Button] {
+        &self.buttons
+    }
+
+    fn escape_action(&self) -> Option<ButtonId> {
+        Some(ButtonId::Back)
+    }
+
+    fn content_bottom(&self) -> f32 {
+        self.content_bottom
+    }
+
+    fn translate(&mut self, dy: f32) {
+        self.title_y += dy;
+        self.content_bottom += dy;
+        for label in &mut self.labels {
+            label.y += dy;
+        }
+        for button in &mut self.buttons {
+            button.bounds.y += dy;
+        }
+    }
+
+    fn render(&self, fonts: Fonts, _ctx: &UiContext) {
+        let text = "Levels";
+        let dims = measure_text(text, Some(fonts.title), TITLE_TEXT_SIZE, 1.0);
+        draw_text_ex(
+            text,
+            (screen_width() - dims.width) / 2.0,
+            self.title_y,
+            TextParams {
+                font_size: TITLE_TEXT_SIZE,
+                color: TEXT_COLOR,
+                font: Some(fonts.title),
+                ..Default::default()
+            },
+        );
+
+        render_section_labels(fonts, &self.labels);
+    }
+}
+
+fn level_item(id: ButtonId, index: usize, level: &Level, progress: &LevelProgress) -> ToggleItem {
+    let stars = progress.stars_for(level);
+    ToggleItem {
+        id,
+        label: format!("{}. {}", index + 1, level.name),
+        sub_label: Some(format!(
+            "{} · {stars}/{MAX_STARS} stars",
+            level.goal.description()
+        )),
+        is_selected: stars == MAX_STARS,
+    }
+}
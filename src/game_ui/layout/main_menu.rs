@@ -1,10 +1,12 @@
 use macroquad::text::{Font, TextParams, draw_text_ex, measure_text};
 use macroquad::window::screen_width;
 
+use crate::app::{LayoutContext, UiContext};
 use crate::constants::ui::{CHROME_HEIGHT, TEXT_COLOR, TITLE_TEXT_SIZE, WINDOW_PADDING};
 
 use super::super::Fonts;
 use super::super::buttons::{Button, ButtonId, ButtonStyle};
+use super::super::screen::Screen;
 use super::compute_button_stack;
 
 pub struct MainMenuLayout {
@@ -12,7 +14,7 @@ pub struct MainMenuLayout {
 }
 
 impl MainMenuLayout {
-    pub fn compute(title_font: &Font, is_existing_game: bool) -> Self {
+    fn compute(title_font: &Font, is_existing_game: bool) -> Self {
         let mut items: Vec<(&str, ButtonId, ButtonStyle)> = vec![];
         if is_existing_game {
             items.push(("Resume", ButtonId::Resume, ButtonStyle::Primary));
@@ -20,6 +22,8 @@ impl MainMenuLayout {
         } else {
             items.push(("New game", ButtonId::NewGame, ButtonStyle::Primary));
         }
+        items.push(("Levels", ButtonId::Levels, ButtonStyle::Secondary));
+        items.push(("Level editor", ButtonId::Editor, ButtonStyle::Secondary));
         items.push(("Settings", ButtonId::Settings, ButtonStyle::Secondary));
         items.push(("High scores", ButtonId::HighScores, ButtonStyle::Secondary));
 
@@ -27,8 +31,18 @@ impl MainMenuLayout {
             buttons: compute_button_stack(title_font, &items, CHROME_HEIGHT + 125.0),
         }
     }
+}
+
+impl Screen for MainMenuLayout {
+    fn build(fonts: Fonts, ctx: &LayoutContext) -> Self {
+        Self::compute(fonts.title, ctx.is_existing_game)
+    }
+
+    fn buttons(&self) -> &[Button] {
+        &self.buttons
+    }
 
-    pub fn render(&self, fonts: Fonts) {
+    fn render(&self, fonts: Fonts, _ctx: &UiContext) {
         let text = "Bleak Blocks";
         let dims = measure_text(text, Some(fonts.title), TITLE_TEXT_SIZE, 1.0);
         draw_text_ex(
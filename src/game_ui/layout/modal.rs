@@ -0,0 +1,154 @@
+use macroquad::{
+    math::Rect,
+    text::{Font, TextParams, draw_text_ex, measure_text},
+    window::{screen_height, screen_width},
+};
+
+use crate::constants::ui::{
+    BODY_TEXT_SIZE, BUTTON_PADDING, CHROME_HEIGHT, LABEL_TEXT_COLOR, MODAL_PADDING, TEXT_COLOR,
+    TITLE_TEXT_SIZE,
+};
+
+use super::super::Fonts;
+use super::super::buttons::{Button, ButtonId, ButtonStyle};
+use super::{compute_button_row, compute_button_stack, render_modal_card};
+
+/// Space between the title and what follows it
+const TITLE_GAP: f32 = 12.0;
+/// Space between the content and the buttons
+const BUTTONS_GAP: f32 = 20.0;
+/// Space between stacked buttons, and between buttons in a row
+const BUTTON_SPACING: f32 = 8.0;
+
+/// A card centered over the screen with a title, optional body text, room for a screen's
+/// own content and its buttons. The buttons sit in a row when they fit across the card,
+/// and are stacked otherwise.
+pub struct Modal {
+    pub rect: Rect,
+    pub buttons: Vec<Button>,
+    /// Top of the space left for the screen's own content, below the body text
+    pub content_y: f32,
+    title: String,
+    body_lines: Vec<String>,
+}
+
+impl Modal {
+    pub fn compute(
+        fonts: Fonts,
+        title: &str,
+        body: Option<&str>,
+        content_h: f32,
+        items: &[(&str, ButtonId, ButtonStyle)],
+    ) -> Self {
+        let screen_w = screen_width();
+        let screen_h = screen_height();
+        let modal_w = (screen_w * 0.6).clamp(280.0, 400.0);
+        let inner_w = modal_w - MODAL_PADDING * 2.0;
+
+        let title_dims = measure_text(title, Some(fonts.title), TITLE_TEXT_SIZE, 1.0);
+        let body_lines = body.map_or(vec![], |text| wrap_text(text, fonts.body, inner_w));
+        let line_h = body_line_height(fonts.body);
+        let body_h = body_lines.len() as f32 * line_h;
+
+        let btn_label_dims = measure_text("Menu", Some(fonts.title), BODY_TEXT_SIZE, 1.0);
+        let btn_h = btn_label_dims.height + 2.0 * BUTTON_PADDING.y + 2.0; // face_h + BLOCK_INSET
+        // Buttons in a row are all as wide as the widest
+        let max_btn_w = items
+            .iter()
+            .map(|(text, _, _)| {
+                measure_text(text, Some(fonts.title), BODY_TEXT_SIZE, 1.0).width
+                    + 2.0 * BUTTON_PADDING.x
+            })
+            .fold(0.0f32, f32::max);
+        let row_w = items.len() as f32 * (max_btn_w + BUTTON_SPACING) - BUTTON_SPACING;
+        let fits_row = row_w <= inner_w;
+        let buttons_h = if fits_row || items.is_empty() {
+            btn_h
+        } else {
+            items.len() as f32 * (btn_h + BUTTON_SPACING) - BUTTON_SPACING
+        };
+
+        let content_total = title_dims.height + TITLE_GAP + body_h + content_h;
+        let modal_h = content_total + BUTTONS_GAP + buttons_h + MODAL_PADDING * 2.0;
+        let modal_x = (screen_w - modal_w) / 2.0;
+        let modal_y = CHROME_HEIGHT + (screen_h - CHROME_HEIGHT - modal_h) / 2.0;
+
+        let buttons_top = modal_y + modal_h - MODAL_PADDING - buttons_h;
+        let baseline = buttons_top + btn_label_dims.offset_y + BUTTON_PADDING.y;
+        let buttons = if fits_row {
+            compute_button_row(fonts.title, items, baseline, BUTTON_SPACING)
+        } else {
+            compute_button_stack(fonts.title, items, baseline)
+        };
+
+        Self {
+            rect: Rect::new(modal_x, modal_y, modal_w, modal_h),
+            buttons,
+            content_y: modal_y + MODAL_PADDING + title_dims.height + TITLE_GAP + body_h,
+            title: title.to_string(),
+            body_lines,
+        }
+    }
+
+    /// Draws the card, title and body text. The screen draws its own content after.
+    pub fn render(&self, fonts: Fonts) {
+        let r = &self.rect;
+        render_modal_card(r);
+
+        let cx = r.x + r.w / 2.0;
+        let mut y = r.y + MODAL_PADDING;
+
+        let title_dims = measure_text(&self.title, Some(fonts.title), TITLE_TEXT_SIZE, 1.0);
+        draw_text_ex(
+            &self.title,
+            cx - title_dims.width / 2.0,
+            y + title_dims.offset_y,
+            TextParams {
+                font_size: TITLE_TEXT_SIZE,
+                color: TEXT_COLOR,
+                font: Some(fonts.title),
+                ..Default::default()
+            },
+        );
+        y += title_dims.height + TITLE_GAP;
+
+        // Every line shares one baseline offset, so lines are evenly spaced
+        let line_dims = measure_text("Mg", Some(fonts.body), BODY_TEXT_SIZE, 1.0);
+        let line_h = body_line_height(fonts.body);
+        for line in &self.body_lines {
+            let dims = measure_text(line, Some(fonts.body), BODY_TEXT_SIZE, 1.0);
+            draw_text_ex(
+                line,
+                cx - dims.width / 2.0,
+                y + line_dims.offset_y,
+                TextParams {
+                    font_size: BODY_TEXT_SIZE,
+                    color: LABEL_TEXT_COLOR,
+                    font: Some(fonts.body),
+                    ..Default::default()
+                },
+            );
+            y += line_h;
+        }
+    }
+}
+
+fn body_line_height(body_font: &Font) -> f32 {
+    measure_text("Mg", Some(body_font), BODY_TEXT_SIZE, 1.0).height + 6.0
+}
+
+/// Breaks `text` into lines no wider than `max_w`, between words
+fn wrap_text(text: &str, body_font: &Font, max_w: f32) -> Vec<String> {
+    let mut lines: Vec<String> = vec![];
+    for word in text.split_whitespace() {
+        if let Some(line) = lines.last_mut() {
+            let candidate = format!("{line} {word}");
+            if measure_text(&candidate, Some(body_font), BODY_TEXT_SIZE, 1.0).width <= max_w {
+                *line = candidate;
+                continue;
+            }
+        }
+        lines.push(word.to_string());
+    }
+    lines
+}
@@ -0,0 +1,67 @@
+use macroquad::text::{TextParams, draw_text_ex, measure_text};
+use num_format::{Locale, ToFormattedString};
+
+use crate::app::{LayoutContext, UiContext};
+use crate::constants::ui::{BODY_TEXT_SIZE, LABEL_TEXT_COLOR};
+
+use super::super::Fonts;
+use super::super::buttons::{Button, ButtonId, ButtonStyle};
+use super::super::screen::Screen;
+use super::{Modal, format_game_time};
+
+pub struct PauseLayout {
+    modal: Modal,
+}
+
+impl PauseLayout {
+    fn compute(fonts: Fonts) -> Self {
+        let items = [
+            ("Resume", ButtonId::Resume, ButtonStyle::Primary),
+            ("Restart", ButtonId::Restart, ButtonStyle::Secondary),
+            ("Settings", ButtonId::Settings, ButtonStyle::Secondary),
+            ("Quit to menu", ButtonId::Menu, ButtonStyle::Secondary),
+        ];
+        let stats_dims = measure_text("Time 00:00", Some(fonts.body), BODY_TEXT_SIZE, 1.0);
+
+        Self {
+            modal: Modal::compute(fonts, "Paused", None, stats_dims.height, &items),
+        }
+    }
+}
+
+impl Screen for PauseLayout {
+    fn build(fonts: Fonts, _ctx: &LayoutContext) -> Self {
+        Self::compute(fonts)
+    }
+
+    fn buttons(&self) -> &[Button] {
+        &self.modal.buttons
+    }
+
+    fn escape_action(&self) -> Option<ButtonId> {
+        Some(ButtonId::Resume)
+    }
+
+    fn render(&self, fonts: Fonts, ctx: &UiContext) {
+        self.modal.render(fonts);
+
+        let stats = format!(
+            "Time {} · Score {}",
+            format_game_time(ctx.game_time),
+            ctx.score.to_formatted_string(&Locale::en)
+        );
+        let stats_dims = measure_text(&stats, Some(fonts.body), BODY_TEXT_SIZE, 1.0);
+        let r = &self.modal.rect;
+        draw_text_ex(
+            &stats,
+            r.x + (r.w - stats_dims.width) / 2.0,
+            self.modal.content_y + stats_dims.offset_y,
+            TextParams {
+                font_size: BODY_TEXT_SIZE,
+                color: LABEL_TEXT_COLOR,
+                font: Some(fonts.body),
+                ..Default::default()
+            },
+        );
+    }
+}
@@ -7,6 +7,7 @@ use macroquad::{
 use num_format::{Locale, ToFormattedString};
 
 use crate::{
+    app::{LayoutContext, UiContext},
     constants::{
         style::{BACKGROUND_COLOR, BLOCK_INSET, GRID_BACKGROUND_COLOR},
         ui::{
@@ -20,6 +21,8 @@ use crate::{
 
 use super::super::Fonts;
 use super::super::buttons::{Button, ButtonId, ButtonStyle};
+use super::super::screen::Screen;
+use super::format_game_time;
 
 pub struct PlayingLayout {
     pub status_panel_height: f32,
@@ -37,7 +40,7 @@ pub fn compute_status_panel_height(title_font: &Font, body_font: &Font) -> f32 {
 }
 
 impl PlayingLayout {
-    pub fn compute(title_font: &Font, body_font: &Font) -> Self {
+    fn compute(title_font: &Font, body_font: &Font) -> Self {
         let status_panel_height = compute_status_panel_height(title_font, body_font);
 
         let panel_y = screen_height() - status_panel_height;
@@ -61,8 +64,26 @@ impl PlayingLayout {
             )],
         }
     }
+}
+
+impl Screen for PlayingLayout {
+    fn build(fonts: Fonts, _ctx: &LayoutContext) -> Self {
+        Self::compute(fonts.title, fonts.body)
+    }
+
+    fn buttons(&self) -> &[Button] {
+        &self.buttons
+    }
 
-    pub fn render(&self, fonts: Fonts, blocks_remaining: u32, score: u32) {
+    fn escape_action(&self) -> Option<ButtonId> {
+        Some(ButtonId::Pause)
+    }
+
+    fn status_panel_height(&self) -> Option<f32> {
+        Some(self.status_panel_height)
+    }
+
+    fn render(&self, fonts: Fonts, ctx: &UiContext) {
         let screen_w = screen_width();
         let screen_h = screen_height();
         let panel_y = screen_h - self.status_panel_height;
@@ -80,7 +101,9 @@ impl PlayingLayout {
         let pause_btn_size = card_h;
         let pause_btn_x = screen_w - WINDOW_PADDING.x - pause_btn_size;
         let cards_end = pause_btn_x - WINDOW_PADDING.x;
-        let card_w = (cards_end - WINDOW_PADDING.x - WINDOW_PADDING.x) / 2.0;
+        let card_count = if ctx.chain.is_some() { 4 } else { 3 };
+        let card_w = (cards_end - WINDOW_PADDING.x - (card_count - 1) as f32 * WINDOW_PADDING.x)
+            / card_count as f32;
 
         let mut card_x = WINDOW_PADDING.x;
         card_x = render_datum_card(
@@ -91,10 +114,10 @@ impl PlayingLayout {
             card_w,
             card_h,
             "Blocks left",
-            &blocks_remaining.to_formatted_string(&Locale::en),
+            &ctx.blocks_remaining.to_formatted_string(&Locale::en),
         );
         card_x += WINDOW_PADDING.x;
-        render_datum_card(
+        card_x = render_datum_card(
             fonts.title,
             fonts.body,
             card_x,
@@ -102,8 +125,32 @@ impl PlayingLayout {
             card_w,
             card_h,
             "Score",
-            &score.to_formatted_string(&Locale::en),
+            &ctx.score.to_formatted_string(&Locale::en),
         );
+        card_x += WINDOW_PADDING.x;
+        card_x = render_datum_card(
+            fonts.title,
+            fonts.body,
+            card_x,
+            card_y,
+            card_w,
+            card_h,
+            "Time",
+            &format_game_time(ctx.game_time),
+        );
+        if let Some(chain) = ctx.chain {
+            card_x += WINDOW_PADDING.x;
+            render_datum_card(
+                fonts.title,
+                fonts.body,
+                card_x,
+                card_y,
+                card_w,
+                card_h,
+                "Chain",
+                &chain.to_formatted_string(&Locale::en),
+            );
+        }
     }
 }
 
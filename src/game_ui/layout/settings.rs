@@ -1,173 +1,399 @@
 use macroquad::{
-    math::Rect,
     text::{Font, TextParams, draw_text_ex, measure_text},
     window::screen_width,
 };
+use strum::IntoEnumIterator;
 
+use crate::app::{LayoutContext, UiContext};
 use crate::{
+    block_registry::block_registry,
+    board_depth::BoardDepth,
+    board_shape::BoardShape,
+    board_style::BoardStyle,
+    clear_effect::ClearEffect,
+    connectivity::Connectivity,
     constants::{
-        style::BLOCK_INSET,
+        custom::{MAX_GRID_SIZE, MIN_COLORS, MIN_GRID_SIZE},
         ui::{
-            BODY_TEXT_SIZE, BUTTON_PADDING, CHROME_HEIGHT, LABEL_TEXT_COLOR, LABEL_TEXT_SIZE,
-            TEXT_COLOR, TITLE_TEXT_SIZE, WINDOW_PADDING,
+            BODY_TEXT_SIZE, BUTTON_PADDING, CHROME_HEIGHT, TEXT_COLOR, TITLE_TEXT_SIZE,
+            WINDOW_PADDING,
         },
     },
     difficulty::Difficulty,
+    easing::Easing,
+    game_mode::GameMode,
+    game_settings::GameSettings,
+    gravity::Gravity,
     grid_size::GridSize,
     orientation::Orientation,
+    scoring::ScoringRule,
+    settle_mode::SettleMode,
 };
 
 use super::super::Fonts;
-use super::super::buttons::{Button, ButtonId, ButtonStyle};
-use super::compute_button_stack;
+use super::super::buttons::{Button, ButtonId, ButtonStyle, SliderId};
+use super::super::screen::Screen;
+use super::{
+    SectionLabel, ToggleItem, ToggleSections, compute_button_stack, render_section_labels,
+};
 
 pub struct SettingsLayout {
-    pub grid_size_label_y: f32,
-    pub orientation_label_y: f32,
-    pub difficulty_label_y: f32,
+    pub title_y: f32,
+    pub content_bottom: f32,
     pub buttons: Vec<Button>,
+    labels: Vec<SectionLabel>,
 }
 
 impl SettingsLayout {
-    pub fn compute(
-        title_font: &Font,
-        body_font: &Font,
-        grid_size: GridSize,
-        difficulty: Difficulty,
-        orientation: Orientation,
-    ) -> Self {
-        let available_w = screen_width() - 2.0 * WINDOW_PADDING.x;
-        let btn_gap = WINDOW_PADDING.x;
-
+    fn compute(title_font: &Font, body_font: &Font, settings: GameSettings) -> Self {
         let title_dims = measure_text("Settings", Some(title_font), TITLE_TEXT_SIZE, 1.0);
-        let mut current_y = CHROME_HEIGHT + WINDOW_PADDING.y + title_dims.height + 16.0;
-
-        let gs_label_dims = measure_text("A", Some(body_font), LABEL_TEXT_SIZE, 1.0);
-        let grid_size_label_y = current_y;
-        current_y += gs_label_dims.height + 8.0;
-
-        let gs_btn_w = (available_w - btn_gap) / 2.0;
-        let gs_main_dims = measure_text("X-Large", Some(title_font), BODY_TEXT_SIZE, 1.0);
-        let gs_sub_dims = measure_text("00×00", Some(body_font), LABEL_TEXT_SIZE, 1.0);
-        let gs_face_h =
-            BUTTON_PADDING.y + gs_main_dims.height + 4.0 + gs_sub_dims.height + BUTTON_PADDING.y;
-        let gs_btn_h = gs_face_h + BLOCK_INSET;
+        let title_y = CHROME_HEIGHT + WINDOW_PADDING.y + title_dims.height;
+        let mut sections = ToggleSections::new(title_font, body_font, title_y + 16.0);
 
         let gs_variants = [
             GridSize::Small,
             GridSize::Medium,
             GridSize::Large,
             GridSize::ExtraLarge,
+            settings.grid_size.as_custom(settings.orientation),
         ];
-        let mut buttons = Vec::new();
-        for (i, gs) in gs_variants.iter().enumerate() {
-            let row = i / 2;
-            let col = i % 2;
-            let x = WINDOW_PADDING.x + col as f32 * (gs_btn_w + btn_gap);
-            let y = current_y + row as f32 * (gs_btn_h + btn_gap);
-            let label = gs.label().to_string();
-            let sub_label = gs.size_hint(orientation);
-            let label_dims = measure_text(&label, Some(title_font), BODY_TEXT_SIZE, 1.0);
-            let sub_label_dims = measure_text(&sub_label, Some(body_font), LABEL_TEXT_SIZE, 1.0);
-            buttons.push(Button::new(
-                ButtonId::SetGridSize(*gs),
-                Rect::new(x, y, gs_btn_w, gs_btn_h),
-                label,
-                label_dims,
-                BODY_TEXT_SIZE,
-                ButtonStyle::Toggle {
-                    is_selected: *gs == grid_size,
-                    sub_label: Some(sub_label),
-                    sub_label_dimensions: Some(sub_label_dims),
-                },
-            ));
+        sections.add(
+            "Grid size",
+            3,
+            gs_variants
+                .iter()
+                .map(|gs| ToggleItem {
+                    id: ButtonId::SetGridSize(*gs),
+                    label: gs.label().to_string(),
+                    sub_label: Some(gs.size_hint(settings.orientation)),
+                    is_selected: *gs == settings.grid_size,
+                })
+                .collect(),
+        );
+        if let GridSize::Custom { rows, cols } = settings.grid_size {
+            sections.add_slider(
+                "Rows",
+                SliderId::GridRows,
+                rows,
+                MIN_GRID_SIZE,
+                MAX_GRID_SIZE,
+            );
+            sections.add_slider(
+                "Columns",
+                SliderId::GridCols,
+                cols,
+                MIN_GRID_SIZE,
+                MAX_GRID_SIZE,
+            );
         }
-        current_y += 2.0 * gs_btn_h + btn_gap;
-
-        current_y += 20.0;
-        let orient_label_dims = measure_text("A", Some(body_font), LABEL_TEXT_SIZE, 1.0);
-        let orientation_label_y = current_y;
-        current_y += orient_label_dims.height + 8.0;
-
-        let orient_btn_w = (available_w - btn_gap) / 2.0;
-        let orient_main_dims = measure_text("Landscape", Some(title_font), BODY_TEXT_SIZE, 1.0);
-        let orient_face_h = BUTTON_PADDING.y + orient_main_dims.height + BUTTON_PADDING.y;
-        let orient_btn_h = orient_face_h + BLOCK_INSET;
 
         let orient_variants = [Orientation::Portrait, Orientation::Landscape];
-        for (i, o) in orient_variants.iter().enumerate() {
-            let x = WINDOW_PADDING.x + i as f32 * (orient_btn_w + btn_gap);
-            let label = o.label().to_string();
-            let label_dims = measure_text(&label, Some(title_font), BODY_TEXT_SIZE, 1.0);
-            buttons.push(Button::new(
-                ButtonId::SetOrientation(*o),
-                Rect::new(x, current_y, orient_btn_w, orient_btn_h),
-                label,
-                label_dims,
-                BODY_TEXT_SIZE,
-                ButtonStyle::Toggle {
-                    is_selected: *o == orientation,
+        sections.add(
+            "Orientation",
+            2,
+            orient_variants
+                .iter()
+                .map(|o| ToggleItem {
+                    id: ButtonId::SetOrientation(*o),
+                    label: o.label().to_string(),
+                    sub_label: None,
+                    is_selected: *o == settings.orientation,
+                })
+                .collect(),
+        );
+
+        let mut diff_variants = Difficulty::presets();
+        diff_variants.push(settings.difficulty.as_custom());
+        sections.add(
+            "Difficulty",
+            diff_variants.len(),
+            diff_variants
+                .iter()
+                .map(|diff| ToggleItem {
+                    id: ButtonId::SetDifficulty(*diff),
+                    label: diff.label().to_string(),
                     sub_label: None,
-                    sub_label_dimensions: None,
-                },
-            ));
+                    is_selected: *diff == settings.difficulty,
+                })
+                .collect(),
+        );
+        if let Difficulty::Custom { colors } = settings.difficulty {
+            let max_colors = block_registry().type_count() as u32;
+            sections.add_slider("Colors", SliderId::Colors, colors, MIN_COLORS, max_colors);
         }
-        current_y += orient_btn_h;
-
-        current_y += 20.0;
-        let diff_label_dims = measure_text("A", Some(body_font), LABEL_TEXT_SIZE, 1.0);
-        let difficulty_label_y = current_y;
-        current_y += diff_label_dims.height + 8.0;
-
-        let diff_btn_w = (available_w - 2.0 * btn_gap) / 3.0;
-        let diff_main_dims = measure_text("Normal", Some(title_font), BODY_TEXT_SIZE, 1.0);
-        let diff_face_h = BUTTON_PADDING.y + diff_main_dims.height + BUTTON_PADDING.y;
-        let diff_btn_h = diff_face_h + BLOCK_INSET;
-
-        let diff_variants = [Difficulty::Easy, Difficulty::Normal, Difficulty::Hard];
-        for (i, diff) in diff_variants.iter().enumerate() {
-            let x = WINDOW_PADDING.x + i as f32 * (diff_btn_w + btn_gap);
-            let label = diff.label().to_string();
-            let label_dims = measure_text(&label, Some(title_font), BODY_TEXT_SIZE, 1.0);
-            buttons.push(Button::new(
-                ButtonId::SetDifficulty(*diff),
-                Rect::new(x, current_y, diff_btn_w, diff_btn_h),
-                label,
-                label_dims,
-                BODY_TEXT_SIZE,
-                ButtonStyle::Toggle {
-                    is_selected: *diff == difficulty,
+
+        let mode_variants = [
+            GameMode::Casual,
+            GameMode::Strict,
+            GameMode::Cascade,
+            GameMode::Darkness,
+            GameMode::Lantern,
+        ];
+        sections.add(
+            "Mode",
+            2,
+            mode_variants
+                .iter()
+                .map(|mode| ToggleItem {
+                    id: ButtonId::SetGameMode(*mode),
+                    label: mode.label().to_string(),
+                    sub_label: None,
+                    is_selected: *mode == settings.rules.mode,
+                })
+                .collect(),
+        );
+
+        let scoring_variants = [
+            ScoringRule::Classic,
+            ScoringRule::Reduced,
+            ScoringRule::Linear,
+            ScoringRule::Combo,
+        ];
+        sections.add(
+            "Scoring",
+            2,
+            scoring_variants
+                .iter()
+                .map(|rule| ToggleItem {
+                    id: ButtonId::SetScoringRule(*rule),
+                    label: rule.label().to_string(),
+                    sub_label: Some(rule.formula_hint().to_string()),
+                    is_selected: *rule == settings.rules.scoring,
+                })
+                .collect(),
+        );
+
+        let shape_variants = [BoardShape::Square, BoardShape::Hex];
+        sections.add(
+            "Board",
+            2,
+            shape_variants
+                .iter()
+                .map(|shape| ToggleItem {
+                    id: ButtonId::SetBoardShape(*shape),
+                    label: shape.label().to_string(),
+                    sub_label: Some(shape.hint().to_string()),
+                    is_selected: *shape == settings.rules.shape,
+                })
+                .collect(),
+        );
+
+        let depth_variants = [BoardDepth::Single, BoardDepth::Double, BoardDepth::Triple];
+        sections.add(
+            "Depth",
+            3,
+            depth_variants
+                .iter()
+                .map(|depth| ToggleItem {
+                    id: ButtonId::SetBoardDepth(*depth),
+                    label: depth.label().to_string(),
+                    sub_label: Some(depth.hint().to_string()),
+                    is_selected: *depth == settings.rules.depth,
+                })
+                .collect(),
+        );
+
+        sections.add(
+            "Haunted blocks",
+            2,
+            [false, true]
+                .iter()
+                .map(|haunted| ToggleItem {
+                    id: ButtonId::SetHaunted(*haunted),
+                    label: if *haunted { "On" } else { "Off" }.to_string(),
                     sub_label: None,
-                    sub_label_dimensions: None,
-                },
-            ));
+                    is_selected: *haunted == settings.rules.haunted,
+                })
+                .collect(),
+        );
+
+        // Only offered where it changes how boards are generated
+        if settings.rules.supports_guaranteed_clear() {
+            sections.add(
+                "Guaranteed clear",
+                2,
+                [false, true]
+                    .iter()
+                    .map(|guaranteed| ToggleItem {
+                        id: ButtonId::SetGuaranteedClear(*guaranteed),
+                        label: if *guaranteed { "On" } else { "Off" }.to_string(),
+                        sub_label: Some(
+                            if *guaranteed {
+                                "Every board can be cleared"
+                            } else {
+                                "Fully random"
+                            }
+                            .to_string(),
+                        ),
+                        is_selected: *guaranteed == settings.rules.guaranteed_clear,
+                    })
+                    .collect(),
+            );
         }
-        current_y += diff_btn_h;
 
-        current_y += 24.0;
+        sections.add(
+            "Board style",
+            3,
+            BoardStyle::iter()
+                .map(|style| ToggleItem {
+                    id: ButtonId::SetBoardStyle(style),
+                    label: style.label().to_string(),
+                    sub_label: Some(style.hint().to_string()),
+                    is_selected: style == settings.rules.style,
+                })
+                .collect(),
+        );
+
+        let connectivity_variants = [
+            Connectivity::Orthogonal,
+            Connectivity::Diagonal,
+            Connectivity::Wraparound,
+        ]
+        .into_iter()
+        .filter(|connectivity| connectivity.applies_to(settings.rules.shape))
+        .collect::<Vec<_>>();
+        sections.add(
+            "Connectivity",
+            connectivity_variants.len(),
+            connectivity_variants
+                .iter()
+                .map(|connectivity| ToggleItem {
+                    id: ButtonId::SetConnectivity(*connectivity),
+                    label: connectivity.label().to_string(),
+                    sub_label: Some(connectivity.hint().to_string()),
+                    is_selected: *connectivity == settings.rules.connectivity,
+                })
+                .collect(),
+        );
+
+        let gravity_variants = [
+            Gravity::DownLeft,
+            Gravity::DownRight,
+            Gravity::DownCenter,
+            Gravity::UpLeft,
+        ];
+        sections.add(
+            "Gravity",
+            2,
+            gravity_variants
+                .iter()
+                .map(|gravity| ToggleItem {
+                    id: ButtonId::SetGravity(*gravity),
+                    label: gravity.label().to_string(),
+                    sub_label: Some(gravity.hint().to_string()),
+                    is_selected: *gravity == settings.gravity,
+                })
+                .collect(),
+        );
+
+        sections.add(
+            "Block motion",
+            4,
+            Easing::iter()
+                .map(|easing| ToggleItem {
+                    id: ButtonId::SetEasing(easing),
+                    label: easing.label().to_string(),
+                    sub_label: None,
+                    is_selected: easing == settings.easing,
+                })
+                .collect(),
+        );
+
+        sections.add(
+            "Settling",
+            2,
+            SettleMode::iter()
+                .map(|mode| ToggleItem {
+                    id: ButtonId::SetSettleMode(mode),
+                    label: mode.label().to_string(),
+                    sub_label: Some(mode.hint().to_string()),
+                    is_selected: mode == settings.settle_mode,
+                })
+                .collect(),
+        );
+
+        sections.add(
+            "Landing bounce",
+            2,
+            [false, true]
+                .iter()
+                .map(|bounce| ToggleItem {
+                    id: ButtonId::SetLandingBounce(*bounce),
+                    label: if *bounce { "On" } else { "Off" }.to_string(),
+                    sub_label: None,
+                    is_selected: *bounce == settings.landing_bounce,
+                })
+                .collect(),
+        );
+
+        sections.add(
+            "Clear effect",
+            3,
+            ClearEffect::iter()
+                .map(|effect| ToggleItem {
+                    id: ButtonId::SetClearEffect(effect),
+                    label: effect.label().to_string(),
+                    sub_label: None,
+                    is_selected: effect == settings.clear_effect,
+                })
+                .collect(),
+        );
+
+        let mut current_y = sections.current_y + 24.0;
         let back_dims = measure_text("Back", Some(title_font), BODY_TEXT_SIZE, 1.0);
         let back_baseline = current_y + back_dims.offset_y + BUTTON_PADDING.y;
+        let mut buttons = sections.buttons;
         buttons.extend(compute_button_stack(
             title_font,
             &[("Back", ButtonId::Back, ButtonStyle::Secondary)],
             back_baseline,
         ));
+        current_y = buttons.last().map_or(current_y, |b| b.bounds.bottom());
 
         Self {
-            grid_size_label_y,
-            orientation_label_y,
-            difficulty_label_y,
+            title_y,
+            content_bottom: current_y,
             buttons,
+            labels: sections.labels,
         }
     }
+}
+
+impl Screen for SettingsLayout {
+    fn build(fonts: Fonts, ctx: &LayoutContext) -> Self {
+        Self::compute(fonts.title, fonts.body, ctx.settings)
+    }
+
+    fn buttons(&self) -> &[Button] {
+        &self.buttons
+    }
+
+    fn escape_action(&self) -> Option<ButtonId> {
+        Some(ButtonId::Back)
+    }
 
-    pub fn render(&self, fonts: Fonts) {
+    fn content_bottom(&self) -> f32 {
+        self.content_bottom
+    }
+
+    fn translate(&mut self, dy: f32) {
+        self.title_y += dy;
+        self.content_bottom += dy;
+        for label in &mut self.labels {
+            label.y += dy;
+        }
+        for button in &mut self.buttons {
+            button.bounds.y += dy;
+        }
+    }
+
+    fn render(&self, fonts: Fonts, _ctx: &UiContext) {
         let text = "Settings";
         let dims = measure_text(text, Some(fonts.title), TITLE_TEXT_SIZE, 1.0);
         draw_text_ex(
             text,
             (screen_width() - dims.width) / 2.0,
-            CHROME_HEIGHT + WINDOW_PADDING.y + dims.height,
+            self.title_y,
             TextParams {
                 font_size: TITLE_TEXT_SIZE,
                 color: TEXT_COLOR,
@@ -176,39 +402,6 @@ impl SettingsLayout {
             },
         );
 
-        let label_dims = measure_text("A", Some(fonts.body), LABEL_TEXT_SIZE, 1.0);
-        draw_text_ex(
-            "GRID SIZE",
-            WINDOW_PADDING.x,
-            self.grid_size_label_y + label_dims.offset_y,
-            TextParams {
-                font_size: LABEL_TEXT_SIZE,
-                color: LABEL_TEXT_COLOR,
-                font: Some(fonts.body),
-                ..Default::default()
-            },
-        );
-        draw_text_ex(
-            "ORIENTATION",
-            WINDOW_PADDING.x,
-            self.orientation_label_y + label_dims.offset_y,
-            TextParams {
-                font_size: LABEL_TEXT_SIZE,
-                color: LABEL_TEXT_COLOR,
-                font: Some(fonts.body),
-                ..Default::default()
-            },
-        );
-        draw_text_ex(
-            "DIFFICULTY",
-            WINDOW_PADDING.x,
-            self.difficulty_label_y + label_dims.offset_y,
-            TextParams {
-                font_size: LABEL_TEXT_SIZE,
-                color: LABEL_TEXT_COLOR,
-                font: Some(fonts.body),
-                ..Default::default()
-            },
-        );
+        render_section_labels(fonts, &self.labels);
     }
 }
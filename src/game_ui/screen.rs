@@ -0,0 +1,34 @@
+use crate::app::{LayoutContext, UiContext};
+
+use super::Fonts;
+use super::buttons::{Button, ButtonId};
+
+/// One screen of the UI. Each screen lays itself out from the app's state whenever the UI
+/// is refreshed, and draws and handles input for itself.
+pub trait Screen {
+    fn build(fonts: Fonts, ctx: &LayoutContext) -> Self
+    where
+        Self: Sized;
+
+    fn buttons(&self) -> &[Button];
+
+    fn render(&self, fonts: Fonts, ctx: &UiContext);
+
+    /// The button Escape stands in for, if any
+    fn escape_action(&self) -> Option<ButtonId> {
+        None
+    }
+
+    /// Y coordinate of the bottom of the screen's content, used to limit scrolling
+    fn content_bottom(&self) -> f32 {
+        0.0
+    }
+
+    /// Moves the screen's content vertically by `dy`. Screens that never scroll ignore it.
+    fn translate(&mut self, _dy: f32) {}
+
+    /// Height of the panel below the board, for screens that show a board
+    fn status_panel_height(&self) -> Option<f32> {
+        None
+    }
+}
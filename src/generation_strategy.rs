@@ -0,0 +1,105 @@
+use macroquad::rand::RandGenerator;
+
+use crate::block::BlockType;
+
+/// How the blocks of a random board are dealt
+#[derive(Copy, Clone, PartialEq)]
+pub enum GenerationStrategy {
+    /// Every block is dealt on its own, by the spawn weights in the block manifest
+    Uniform,
+    /// Each block copies the type of the block above or to its left with chance `bias`,
+    /// growing larger groups
+    Clustered { bias: f32 },
+    /// Each band of `width` rows has a type of its own, which each of its blocks takes with
+    /// chance `strength`
+    Striped { width: u32, strength: f32 },
+    /// The right half of the board mirrors the left half
+    Symmetric,
+    /// Spawn weights are scaled by `weights`, given in block type order. Types without a
+    /// weight are scaled by 1.
+    Weighted { weights: &'static [f32] },
+}
+
+impl GenerationStrategy {
+    /// Deals a rows × cols board from `block_types`, ordered row by row, top to bottom
+    pub fn generate(
+        &self,
+        rows: u32,
+        cols: u32,
+        block_types: &[BlockType],
+        rng: &RandGenerator,
+    ) -> Vec<BlockType> {
+        let weights: Vec<f32> = block_types
+            .iter()
+            .enumerate()
+            .map(|(i, block_type)| match self {
+                GenerationStrategy::Weighted { weights } => {
+                    block_type.spawn_weight() * weights.get(i).copied().unwrap_or(1.0)
+                }
+                _ => block_type.spawn_weight(),
+            })
+            .collect();
+        let total: f32 = weights.iter().sum();
+        let deal = || {
+            let mut pick = rng.gen_range(0.0, total);
+            for (block_type, weight) in block_types.iter().zip(&weights) {
+                if pick < *weight {
+                    return block_type.clone();
+                }
+                pick -= weight;
+            }
+            block_types[block_types.len() - 1].clone()
+        };
+        let chance = |probability: f32| rng.gen_range(0.0, 1.0) < probability;
+
+        match *self {
+            GenerationStrategy::Uniform | GenerationStrategy::Weighted { .. } => {
+                (0..rows * cols).map(|_| deal()).collect()
+            }
+            GenerationStrategy::Clustered { bias } => {
+                let mut board: Vec<BlockType> = Vec::with_capacity((rows * cols) as usize);
+                for row in 0..rows {
+                    for col in 0..cols {
+                        let index = (row * cols + col) as usize;
+                        let above = (row > 0).then(|| &board[index - cols as usize]);
+                        let left = (col > 0).then(|| &board[index - 1]);
+                        let neighbor = match (above, left) {
+                            (Some(above), Some(left)) => {
+                                Some(if chance(0.5) { above } else { left })
+                            }
+                            (neighbor, None) | (None, neighbor) => neighbor,
+                        };
+                        let block_type = match neighbor {
+                            Some(neighbor) if chance(bias) => neighbor.clone(),
+                            _ => deal(),
+                        };
+                        board.push(block_type);
+                    }
+                }
+                board
+            }
+            GenerationStrategy::Striped { width, strength } => {
+                let bands: Vec<BlockType> = (0..rows.div_ceil(width)).map(|_| deal()).collect();
+                (0..rows * cols)
+                    .map(|i| {
+                        if chance(strength) {
+                            bands[(i / cols / width) as usize].clone()
+                        } else {
+                            deal()
+                        }
+                    })
+                    .collect()
+            }
+            GenerationStrategy::Symmetric => {
+                let half = cols.div_ceil(2);
+                let left: Vec<BlockType> = (0..rows * half).map(|_| deal()).collect();
+                (0..rows * cols)
+                    .map(|i| {
+                        let (row, col) = (i / cols, i % cols);
+                        left[(row * half + col.min(cols - 1 - col)) as usize].clone()
+                    })
+                    .collect()
+            }
+        }
+    }
+}
@@ -0,0 +1,56 @@
+use macroquad::math::{Vec2, vec2};
+
+use crate::constants::physics::FORCE;
+
+/// Which way blocks fall into gaps, and which way columns collapse into empty columns
+#[derive(Copy, Clone, PartialEq, Default)]
+pub enum Gravity {
+    #[default]
+    DownLeft,
+    DownRight,
+    DownCenter,
+    /// Anti-gravity: blocks rise to the top of the board
+    UpLeft,
+}
+
+impl Gravity {
+    pub fn label(self) -> &'static str {
+        match self {
+            Gravity::DownLeft => "Classic",
+            Gravity::DownRight => "Mirrored",
+            Gravity::DownCenter => "Centered",
+            Gravity::UpLeft => "Haunted",
+        }
+    }
+
+    pub fn hint(self) -> &'static str {
+        match self {
+            Gravity::DownLeft => "Down · left",
+            Gravity::DownRight => "Down · right",
+            Gravity::DownCenter => "Down · center",
+            Gravity::UpLeft => "Up · left",
+        }
+    }
+
+    pub fn falls_up(self) -> bool {
+        self == Gravity::UpLeft
+    }
+
+    /// Force applied to blocks falling into gaps within their column
+    pub fn fall_force(self) -> Vec2 {
+        if self.falls_up() {
+            vec2(0.0, -FORCE)
+        } else {
+            vec2(0.0, FORCE)
+        }
+    }
+
+    /// Force applied to columns collapsing into empty columns. Centered columns converge
+    /// from both sides, so only the axis of this force matters for them.
+    pub fn shift_force(self) -> Vec2 {
+        match self {
+            Gravity::DownLeft | Gravity::UpLeft => vec2(-FORCE, 0.0),
+            Gravity::DownRight | Gravity::DownCenter => vec2(FORCE, 0.0),
+        }
+    }
+}
@@ -1,23 +1,38 @@
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 
 use macroquad::{
     math::{Rect, Vec2},
-    rand,
+    rand::RandGenerator,
 };
-use strum::IntoEnumIterator;
 
 use crate::{
     block::{Block, BlockType},
+    block_move::BlockMove,
+    board_shape::{BoardShape, HEX_HEIGHT_RATIO},
+    clearable_board::generate_clearable_board,
+    connectivity::Connectivity,
+    constants::{darkness::REVEAL_DURATION, haunted::FLICKER_DURATION},
     coordinate::{Coordinate, coordinate},
+    game_settings::GameSettings,
+    gravity::Gravity,
+    level::Level,
+    rules::GameRules,
 };
 
 pub struct GridLayout {
     pub rows: u32,
     pub cols: u32,
+    /// Depth of the stack of blocks each cell starts with
+    pub layers: u32,
     pub blocks_remaining: u32,
     pub block_size: f32,
+    pub connectivity: Connectivity,
+    pub shape: BoardShape,
+    /// Whether the board was generated so that it can be cleared completely
+    pub is_clearable: bool,
     rect: Rect,
-    /// Ordered row by row, top to bottom
+    /// Ordered layer by layer from the bottom up, then row by row, top to bottom. Each cell's
+    /// blocks are stacked from the bottom layer without gaps, and only the topmost is in play.
     blocks: Vec<Option<Block>>,
 }
 
@@ -25,19 +40,87 @@ impl GridLayout {
     pub fn new(
         position: Vec2,
         dimensions: Vec2,
-        rows: u32,
-        cols: u32,
-        block_type_count: usize,
+        settings: &GameSettings,
+        rng: &RandGenerator,
     ) -> Self {
-        let block_size = (dimensions.x / cols as f32).min(dimensions.y / rows as f32);
-        let block_types = BlockType::iter().take(block_type_count).collect::<Vec<_>>();
+        let (rows, cols) = settings.grid_size.grid_dims(settings.orientation);
+        let rules = &settings.rules;
+        let block_types = BlockType::all()
+            .take(settings.difficulty.block_type_count())
+            .collect::<Vec<_>>();
+        let haunted_rate = if rules.haunted {
+            settings.difficulty.haunted_rate()
+        } else {
+            0.0
+        };
 
+        let layers = rules.depth.layers();
+        let clearable_board = (rules.guaranteed_clear && rules.supports_guaranteed_clear())
+            .then(|| {
+                generate_clearable_board(rows, cols, &block_types, rules, settings.gravity, rng)
+            })
+            .flatten();
+        let is_clearable = clearable_board.is_some();
+        let block_types = match clearable_board {
+            Some(board) => board
+                .into_iter()
+                .map(|block_type| block_type.expect("clearable boards are full"))
+                .collect(),
+            None => {
+                let strategy = settings.difficulty.generation_strategy(rules.style);
+                (0..layers)
+                    .flat_map(|_| strategy.generate(rows, cols, &block_types, rng))
+                    .collect::<Vec<_>>()
+            }
+        };
         let mut blocks = vec![];
-        for _ in 0..(rows * cols) {
-            let block_type = block_types[rand::rand() as usize % block_types.len()].clone();
-            blocks.push(Some(Block::new(block_type)));
+        for block_type in block_types {
+            if rng.gen_range(0.0, 1.0) < haunted_rate {
+                blocks.push(Some(Block::haunted(block_type)));
+            } else {
+                blocks.push(Some(Block::new(block_type)));
+            }
         }
 
+        GridLayout {
+            is_clearable,
+            ..GridLayout::with_blocks(position, dimensions, rows, cols, layers, rules, blocks)
+        }
+    }
+
+    /// Lays out a level's board exactly as it was designed, in place of a random one
+    pub fn from_level(position: Vec2, dimensions: Vec2, level: &Level) -> Self {
+        let mut blocks = vec![];
+        for row in 0..level.rows {
+            for col in 0..level.cols {
+                blocks.push(level.block_type_at(row, col).map(Block::new));
+            }
+        }
+
+        GridLayout::with_blocks(
+            position,
+            dimensions,
+            level.rows,
+            level.cols,
+            1,
+            &level.rules,
+            blocks,
+        )
+    }
+
+    fn with_blocks(
+        position: Vec2,
+        dimensions: Vec2,
+        rows: u32,
+        cols: u32,
+        layers: u32,
+        rules: &GameRules,
+        blocks: Vec<Option<Block>>,
+    ) -> Self {
+        let extent = rules.shape.extent(rows, cols);
+        let block_size = (dimensions.x / extent.x).min(dimensions.y / extent.y);
+        let blocks_remaining = blocks.iter().flatten().count() as u32;
+
         GridLayout {
             rect: Rect {
                 x: position.x,
@@ -47,14 +130,19 @@ impl GridLayout {
             },
             rows,
             cols,
+            layers,
             block_size,
+            connectivity: rules.connectivity,
+            shape: rules.shape,
+            is_clearable: false,
             blocks,
-            blocks_remaining: cols * rows,
+            blocks_remaining,
         }
     }
 
     pub fn resize(&mut self, position: Vec2, dimensions: Vec2) {
-        self.block_size = (dimensions.x / self.cols as f32).min(dimensions.y / self.rows as f32);
+        let extent = self.extent();
+        self.block_size = (dimensions.x / extent.x).min(dimensions.y / extent.y);
         self.rect = Rect {
             x: position.x,
             y: position.y,
@@ -63,11 +151,60 @@ impl GridLayout {
         };
     }
 
+    /// Top-left corner of the cell's bounding box
     pub fn grid_to_world(&self, position: Coordinate) -> Vec2 {
-        Vec2::new(
-            self.x() + position.col as f32 * self.block_size,
-            self.y() + position.row as f32 * self.block_size,
-        )
+        match self.shape {
+            BoardShape::Square => Vec2::new(
+                self.x() + position.col as f32 * self.block_size,
+                self.y() + position.row as f32 * self.block_size,
+            ),
+            BoardShape::Hex => {
+                let cell_h = self.cell_height();
+                let column_drop = if position.col % 2 == 1 {
+                    cell_h / 2.0
+                } else {
+                    0.0
+                };
+                Vec2::new(
+                    self.x() + position.col as f32 * self.block_size * 0.75,
+                    self.y() + position.row as f32 * cell_h + column_drop,
+                )
+            }
+        }
+    }
+
+    /// Size of the board measured in cells
+    pub fn extent(&self) -> Vec2 {
+        self.shape.extent(self.rows, self.cols)
+    }
+
+    /// Converts a point on screen to one measured from the board's corner in block sizes,
+    /// which stays on the same spot of the board when it is resized
+    pub fn world_to_board(&self, world_pos: Vec2) -> Vec2 {
+        (world_pos - self.rect.point()) / self.block_size
+    }
+
+    pub fn board_to_world(&self, board_pos: Vec2) -> Vec2 {
+        self.rect.point() + board_pos * self.block_size
+    }
+
+    pub fn cell_center(&self, position: Coordinate) -> Vec2 {
+        self.grid_to_world(position) + Vec2::new(self.block_size, self.cell_height()) / 2.0
+    }
+
+    /// Cells are `block_size` wide; hex cells are shorter than they are wide
+    pub fn cell_height(&self) -> f32 {
+        match self.shape {
+            BoardShape::Square => self.block_size,
+            BoardShape::Hex => self.block_size * HEX_HEIGHT_RATIO,
+        }
+    }
+
+    pub fn get_neighbors(&self, position: Coordinate) -> Vec<Coordinate> {
+        match self.shape {
+            BoardShape::Square => position.get_neighbors(self.rows, self.cols, self.connectivity),
+            BoardShape::Hex => position.get_hex_neighbors(self.rows, self.cols, self.connectivity),
+        }
     }
 
     pub fn x(&self) -> f32 {
@@ -92,23 +229,83 @@ impl GridLayout {
         }
 
         let local_pos = world_pos - self.rect.point();
-        let row = (local_pos.y / self.block_size) as u32;
-        let col = (local_pos.x / self.block_size) as u32;
+        match self.shape {
+            BoardShape::Square => {
+                let row = (local_pos.y / self.block_size) as u32;
+                let col = (local_pos.x / self.block_size) as u32;
+
+                Some(coordinate(row, col))
+            }
+            BoardShape::Hex => self.world_to_hex(world_pos, local_pos),
+        }
+    }
+
+    /// Hexes tile the board as the cells closest to each center, so the hex containing a
+    /// point is whichever nearby center is closest to it
+    fn world_to_hex(&self, world_pos: Vec2, local_pos: Vec2) -> Option<Coordinate> {
+        let approx_col = (local_pos.x / (self.block_size * 0.75)) as i64;
+        let approx_row = (local_pos.y / self.cell_height()) as i64;
+
+        let mut closest: Option<(Coordinate, f32)> = None;
+        for col in (approx_col - 1)..=(approx_col + 1) {
+            for row in (approx_row - 1)..=(approx_row + 1) {
+                if !(0..self.cols as i64).contains(&col) || !(0..self.rows as i64).contains(&row) {
+                    continue;
+                }
+
+                let position = coordinate(row as u32, col as u32);
+                let distance = self.cell_center(position).distance_squared(world_pos);
+                if closest.is_none_or(|(_, closest_distance)| distance < closest_distance) {
+                    closest = Some((position, distance));
+                }
+            }
+        }
+
+        // Corners of the board's rectangle fall outside every hex
+        closest
+            .filter(|&(_, distance)| distance <= (self.block_size / 2.0).powi(2))
+            .map(|(position, _)| position)
+    }
+
+    fn get_index(&self, position: Coordinate, layer: u32) -> usize {
+        ((layer * self.rows + position.row) * self.cols + position.col) as usize
+    }
 
-        Some(coordinate(row, col))
+    /// The layer of the topmost block at `position`
+    pub fn top_layer(&self, position: Coordinate) -> Option<u32> {
+        (0..self.layers)
+            .rev()
+            .find(|&layer| self.get_block_at(position, layer).is_some())
     }
 
-    fn get_index(&self, position: Coordinate) -> usize {
-        (position.row * self.cols + position.col) as usize
+    /// Number of blocks stacked at `position`
+    pub fn depth_at(&self, position: Coordinate) -> u32 {
+        self.top_layer(position).map_or(0, |layer| layer + 1)
     }
 
+    /// The topmost block at `position`, which is the one in play
     pub fn get_block(&self, position: Coordinate) -> Option<&Block> {
-        let index = self.get_index(position);
+        self.get_block_at(position, self.top_layer(position)?)
+    }
+
+    pub fn get_block_at(&self, position: Coordinate, layer: u32) -> Option<&Block> {
+        let index = self.get_index(position, layer);
         self.blocks.get(index)?.as_ref()
     }
 
+    fn get_block_mut(&mut self, position: Coordinate) -> Option<&mut Block> {
+        let index = self.get_index(position, self.top_layer(position)?);
+        self.blocks.get_mut(index)?.as_mut()
+    }
+
+    /// Removes the topmost block at `position`, exposing the one beneath it
     pub fn take_block(&mut self, position: Coordinate) -> Option<Block> {
-        let index = self.get_index(position);
+        let layer = self.top_layer(position)?;
+        self.take_block_at(position, layer)
+    }
+
+    pub fn take_block_at(&mut self, position: Coordinate, layer: u32) -> Option<Block> {
+        let index = self.get_index(position, layer);
 
         if index >= self.blocks.len() {
             return None;
@@ -117,10 +314,13 @@ impl GridLayout {
         self.blocks[index].take()
     }
 
+    /// Hidden blocks never belong to a region
     pub fn get_block_region(&self, start: Coordinate) -> HashSet<Coordinate> {
         let mut region = HashSet::new();
         let block_type = 'block_type: {
-            if let Some(block) = self.get_block(start) {
+            if let Some(block) = self.get_block(start)
+                && !block.hidden
+            {
                 break 'block_type block.block_type.clone();
             } else {
                 return region;
@@ -130,8 +330,8 @@ impl GridLayout {
         let mut neighbors = vec![start];
         while let Some(position) = neighbors.pop() {
             if let Some(block) = self.get_block(position) {
-                if !region.contains(&position) && block.block_type == block_type {
-                    neighbors.extend(position.get_neighbors(self.rows, self.cols));
+                if !region.contains(&position) && !block.hidden && block.block_type == block_type {
+                    neighbors.extend(self.get_neighbors(position));
                     region.insert(position);
                 }
             }
@@ -140,41 +340,256 @@ impl GridLayout {
         region
     }
 
-    // Returns number of blocks removed
-    pub fn remove_block_region(&mut self, start_position: Vec2) -> u32 {
-        let start_coordinate = self.world_to_grid(start_position);
+    /// Returns true if any group of at least `min_size` blocks remains on the board
+    pub fn has_region_of_size(&self, min_size: u32) -> bool {
+        let mut visited = HashSet::new();
+        for row in 0..self.rows {
+            for col in 0..self.cols {
+                let position = coordinate(row, col);
+                if visited.contains(&position) || self.is_empty_at(position) {
+                    continue;
+                }
 
-        if start_coordinate.is_none() {
-            return 0;
+                let region = self.get_block_region(position);
+                if region.len() as u32 >= min_size {
+                    return true;
+                }
+                visited.extend(region);
+            }
         }
-        let start_coordinate = start_coordinate.unwrap();
 
+        false
+    }
+
+    // Returns the blocks removed. Groups smaller than `min_size` are left in place.
+    pub fn remove_block_region(
+        &mut self,
+        start_coordinate: Coordinate,
+        min_size: u32,
+    ) -> Vec<(Coordinate, BlockType)> {
         let block_positions = self
             .get_block_region(start_coordinate)
             .into_iter()
             .collect::<Vec<_>>();
 
-        for &position in block_positions.iter() {
-            self.take_block(position);
+        if (block_positions.len() as u32) < min_size {
+            return vec![];
         }
 
-        self.blocks_remaining -= block_positions.len() as u32;
-        block_positions.len() as u32
+        self.remove_blocks(&block_positions)
+    }
+
+    // Returns the blocks removed and where they were. Blocks exposed beneath the cleared
+    // cells and hidden blocks next to them are revealed.
+    pub fn remove_blocks(&mut self, positions: &[Coordinate]) -> Vec<(Coordinate, BlockType)> {
+        let removed = positions
+            .iter()
+            .filter_map(|&position| {
+                self.take_block(position)
+                    .map(|block| (position, block.block_type))
+            })
+            .collect::<Vec<_>>();
+
+        self.blocks_remaining -= removed.len() as u32;
+        for &position in positions {
+            if let Some(block) = self.get_block_mut(position) {
+                block.reveal();
+            }
+        }
+        self.reveal_neighbors(positions);
+        removed
     }
 
-    pub fn find_falling_blocks(&self) -> Option<Vec<(Coordinate, Coordinate)>> {
+    /// Hides every block outside the `lit_rows` rows nearest the edge blocks fall towards
+    pub fn darken(&mut self, lit_rows: u32, gravity: Gravity) {
+        for row in 0..self.rows {
+            let lit = if gravity.falls_up() {
+                row < lit_rows
+            } else {
+                row + lit_rows >= self.rows
+            };
+            if lit {
+                continue;
+            }
+
+            for col in 0..self.cols {
+                for layer in 0..self.layers {
+                    let index = self.get_index(coordinate(row, col), layer);
+                    if let Some(block) = &mut self.blocks[index] {
+                        block.hide();
+                    }
+                }
+            }
+        }
+    }
+
+    /// Reveals hidden blocks next to any of `positions`
+    pub fn reveal_neighbors(&mut self, positions: &[Coordinate]) {
+        for &position in positions {
+            for neighbor in self.get_neighbors(position) {
+                if let Some(block) = self.get_block_mut(neighbor) {
+                    block.reveal();
+                }
+            }
+        }
+    }
+
+    /// Reveals hidden blocks next to the cells a block passes through when falling
+    /// from `from` to `to`
+    pub fn reveal_along_fall(&mut self, from: Coordinate, to: Coordinate) {
+        if from.col != to.col {
+            return;
+        }
+
+        let path = (from.row.min(to.row)..=from.row.max(to.row))
+            .map(|row| coordinate(row, from.col))
+            .collect::<Vec<_>>();
+        self.reveal_neighbors(&path);
+    }
+
+    /// Returns true if any block was hidden
+    pub fn reveal_all(&mut self) -> bool {
+        let mut revealed = false;
+        for block in self.blocks.iter_mut().flatten() {
+            revealed |= block.reveal();
+        }
+        revealed
+    }
+
+    /// Advances the reveal and flicker animations of every block
+    pub fn update_block_animations(&mut self, time_delta: f32) {
+        for block in self.blocks.iter_mut().flatten() {
+            if block.is_revealing() {
+                block.reveal_progress =
+                    (block.reveal_progress + time_delta / REVEAL_DURATION).min(1.0);
+            }
+            block.flicker = (block.flicker - time_delta).max(0.0);
+        }
+    }
+
+    /// Switches every haunted block to a different type still on the board. Blocks are
+    /// visited in storage order so the same `rng` state always haunts the board the same
+    /// way. Returns the cells whose topmost block changed.
+    pub fn haunt(&mut self, rng: &RandGenerator) -> Vec<Coordinate> {
+        let types_in_play = BlockType::all()
+            .filter(|block_type| self.contains_type(block_type))
+            .collect::<Vec<_>>();
+
+        let mut changed = vec![];
+        for index in 0..self.blocks.len() {
+            let Some(block) = &mut self.blocks[index] else {
+                continue;
+            };
+            if !block.haunted {
+                continue;
+            }
+
+            let choices = types_in_play
+                .iter()
+                .filter(|&block_type| *block_type != block.block_type)
+                .collect::<Vec<_>>();
+            if choices.is_empty() {
+                continue;
+            }
+
+            block.block_type = choices[rng.rand() as usize % choices.len()].clone();
+            block.flicker = FLICKER_DURATION;
+
+            let cell = index % (self.rows * self.cols) as usize;
+            let layer = (index / (self.rows * self.cols) as usize) as u32;
+            let position = coordinate(cell as u32 / self.cols, cell as u32 % self.cols);
+            if self.top_layer(position) == Some(layer) {
+                changed.push(position);
+            }
+        }
+
+        changed
+    }
+
+    /// Groups of at least `min_size` blocks that include one of `positions`
+    pub fn find_regions_touching(
+        &self,
+        positions: &HashSet<Coordinate>,
+        min_size: u32,
+    ) -> Vec<HashSet<Coordinate>> {
+        let mut visited = HashSet::new();
+        let mut regions = vec![];
+
+        for &position in positions {
+            if visited.contains(&position) {
+                continue;
+            }
+
+            let region = self.get_block_region(position);
+            visited.extend(region.iter().copied());
+            if region.len() as u32 >= min_size {
+                regions.push(region);
+            }
+        }
+
+        regions
+    }
+
+    /// Each layer falls on its own, so a block only falls into gaps in its own layer
+    pub fn find_falling_blocks(&self, gravity: Gravity) -> Option<Vec<BlockMove>> {
         let mut result = vec![];
 
-        for col in 0..self.cols {
-            let mut rows_to_fall = 0u32;
+        for layer in 0..self.layers {
+            for col in 0..self.cols {
+                let mut rows_to_fall = 0u32;
+
+                // Walk each column starting from the edge blocks fall towards
+                for i in 0..self.rows {
+                    let row = if gravity.falls_up() {
+                        i
+                    } else {
+                        self.rows - 1 - i
+                    };
+                    let position = coordinate(row, col);
+                    if self.get_block_at(position, layer).is_none() {
+                        rows_to_fall += 1;
+                    } else if rows_to_fall > 0 {
+                        // We encountered a block after a gap (the block needs to fall)
+                        let target_row = if gravity.falls_up() {
+                            row - rows_to_fall
+                        } else {
+                            row + rows_to_fall
+                        };
+                        result.push(BlockMove {
+                            from: position,
+                            to: coordinate(target_row, col),
+                            layer,
+                            via: None,
+                        });
+                    }
+                }
+            }
+        }
+
+        if result.is_empty() {
+            None
+        } else {
+            Some(result)
+        }
+    }
 
-            for row in (0..self.rows).rev() {
+    pub fn find_shifting_blocks(&self, gravity: Gravity) -> Option<Vec<BlockMove>> {
+        let mut result = vec![];
+
+        for (col, target_col) in self.column_targets(gravity) {
+            if target_col == col {
+                continue;
+            }
+
+            for row in 0..self.rows {
                 let position = coordinate(row, col);
-                if self.is_empty_at(position) {
-                    rows_to_fall += 1;
-                } else if rows_to_fall > 0 {
-                    // We encountered a block after a gap (the block needs to fall)
-                    result.push((position, coordinate(row + rows_to_fall, col)));
+                for layer in 0..self.depth_at(position) {
+                    result.push(BlockMove {
+                        from: position,
+                        to: coordinate(row, target_col),
+                        layer,
+                        via: None,
+                    });
                 }
             }
         }
@@ -186,20 +601,59 @@ impl GridLayout {
         }
     }
 
-    pub fn find_shifting_blocks(&self) -> Option<Vec<(Coordinate, Coordinate)>> {
+    /// Each non-empty column paired with the column it shifts to. Non-empty columns close
+    /// ranks, packed against the edge (or middle) gravity pulls to.
+    fn column_targets(&self, gravity: Gravity) -> Vec<(u32, u32)> {
+        let occupied_cols = (0..self.cols)
+            .filter(|&col| !self.is_column_empty(col))
+            .collect::<Vec<_>>();
+        let free_cols = self.cols - occupied_cols.len() as u32;
+        let first_col = match gravity {
+            Gravity::DownLeft | Gravity::UpLeft => 0,
+            Gravity::DownRight => free_cols,
+            Gravity::DownCenter => free_cols / 2,
+        };
+
+        occupied_cols
+            .into_iter()
+            .enumerate()
+            .map(|(i, col)| (col, first_col + i as u32))
+            .collect()
+    }
+
+    /// Moves every block straight to where it ends up once it has fallen and its column has
+    /// shifted, for animating both in one pass. Falling never empties a column, so shifts can
+    /// be worked out before blocks fall.
+    pub fn find_settling_blocks(&self, gravity: Gravity) -> Option<Vec<BlockMove>> {
+        let falls: HashMap<(Coordinate, u32), Coordinate> = self
+            .find_falling_blocks(gravity)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|block_move| ((block_move.from, block_move.layer), block_move.to))
+            .collect();
+
         let mut result = vec![];
+        for (col, target_col) in self.column_targets(gravity) {
+            for row in 0..self.rows {
+                let position = coordinate(row, col);
+                for layer in 0..self.layers {
+                    if self.get_block_at(position, layer).is_none() {
+                        continue;
+                    }
 
-        let mut columns_to_shift = 0;
-        for col in 0..self.cols {
-            if self.is_column_empty(col) {
-                columns_to_shift += 1;
-            } else if columns_to_shift > 0 {
-                // We encountered a non-empty column after an empty column (columns need to shift)
-                for row in 0..self.rows {
-                    let position = coordinate(row, col);
-                    if !self.is_empty_at(position) {
-                        result.push((position, coordinate(row, col - columns_to_shift)));
+                    let landing = falls.get(&(position, layer)).copied();
+                    let fallen = landing.unwrap_or(position);
+                    let to = coordinate(fallen.row, target_col);
+                    if to == position {
+                        continue;
                     }
+
+                    result.push(BlockMove {
+                        from: position,
+                        to,
+                        layer,
+                        via: landing.filter(|_| target_col != col),
+                    });
                 }
             }
         }
@@ -221,14 +675,21 @@ impl GridLayout {
         true
     }
 
-    pub fn place_block(&mut self, position: Coordinate, block: Block) {
-        let index = self.get_index(position);
+    pub fn place_block_at(&mut self, position: Coordinate, layer: u32, block: Block) {
+        let index = self.get_index(position, layer);
 
         if index < self.blocks.len() {
             self.blocks[index].replace(block);
         }
     }
 
+    pub fn contains_type(&self, block_type: &BlockType) -> bool {
+        self.blocks
+            .iter()
+            .flatten()
+            .any(|block| block.block_type == *block_type)
+    }
+
     pub fn is_empty_at(&self, position: Coordinate) -> bool {
         self.get_block(position).is_none()
     }
@@ -7,6 +7,11 @@ pub enum GridSize {
     Medium,
     Large,
     ExtraLarge,
+    /// A board of exactly `rows` × `cols`, whatever the orientation
+    Custom {
+        rows: u32,
+        cols: u32,
+    },
 }
 
 impl GridSize {
@@ -16,6 +21,7 @@ impl GridSize {
             GridSize::Medium => "Medium",
             GridSize::Large => "Large",
             GridSize::ExtraLarge => "X-Large",
+            GridSize::Custom { .. } => "Custom",
         }
     }
 
@@ -24,9 +30,16 @@ impl GridSize {
         format!("{rows}×{cols}")
     }
 
+    /// A custom size with the same dimensions, for the player to adjust from
+    pub fn as_custom(self, orientation: Orientation) -> GridSize {
+        let (rows, cols) = self.grid_dims(orientation);
+        GridSize::Custom { rows, cols }
+    }
+
     /// Returns (rows, cols). Portrait = more rows, landscape = more cols.
     pub fn grid_dims(self, orientation: Orientation) -> (u32, u32) {
         let (portrait_rows, portrait_cols) = match self {
+            GridSize::Custom { rows, cols } => return (rows, cols),
             GridSize::Small => (8, 6),
             GridSize::Medium => (13, 10),
             GridSize::Large => (18, 14),
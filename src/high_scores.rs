@@ -5,20 +5,60 @@ use std::path::PathBuf;
 use directories::ProjectDirs;
 use serde::{Deserialize, Serialize};
 
+use crate::board_style::BoardStyle;
+use crate::constants::scoring::BOARD_CLEAR_BONUS;
 use crate::difficulty::Difficulty;
 use crate::grid_size::GridSize;
+use crate::rules::GameRules;
+use crate::score_bucket::ScoreBucket;
 
 const MAX_ENTRIES: usize = 5;
 const SAVE_FILE: &str = "high_scores.bin";
+const CURRENT_VERSION: u8 = 2;
 
 #[derive(Serialize, Deserialize, Clone)]
 pub struct HighScoreEntry {
     pub score: u32,
 }
 
+type Buckets<K> = HashMap<K, Vec<HighScoreEntry>>;
+
 #[derive(Serialize, Deserialize)]
 struct HighScoresPayloadV1 {
-    entries: HashMap<(GridSize, Difficulty), Vec<HighScoreEntry>>,
+    entries: Buckets<(GridSize, Difficulty)>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct HighScoresPayloadV2 {
+    entries: Buckets<ScoreBucket>,
+}
+
+impl From<HighScoresPayloadV1> for HighScoresPayloadV2 {
+    /// Every game before V2 was played under the default rules on a preset grid size and
+    /// difficulty, with every block type equally likely. Single blocks could always be
+    /// cleared, so every one of those games ended on an empty board and is given the bonus
+    /// a cleared board earns now.
+    fn from(v1: HighScoresPayloadV1) -> Self {
+        let rules = GameRules {
+            style: BoardStyle::Uniform,
+            ..GameRules::default()
+        };
+        Self {
+            entries: v1
+                .entries
+                .into_iter()
+                .map(|((grid_size, difficulty), scores)| {
+                    let scores = scores
+                        .into_iter()
+                        .map(|entry| HighScoreEntry {
+                            score: entry.score.saturating_add(BOARD_CLEAR_BONUS),
+                        })
+                        .collect();
+                    (ScoreBucket::new(grid_size, difficulty, rules), scores)
+                })
+                .collect(),
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize)]
@@ -28,7 +68,7 @@ struct VersionedHighScores {
 }
 
 pub struct HighScores {
-    payload: HighScoresPayloadV1,
+    payload: HighScoresPayloadV2,
     save_path: Option<PathBuf>,
 }
 
@@ -40,35 +80,34 @@ impl HighScores {
             .and_then(|p| fs::read(p).ok())
             .and_then(|bytes| postcard::from_bytes::<VersionedHighScores>(&bytes).ok())
             .and_then(|envelope| match envelope.version {
-                1 => postcard::from_bytes::<HighScoresPayloadV1>(&envelope.data).ok(),
+                1 => postcard::from_bytes::<HighScoresPayloadV1>(&envelope.data)
+                    .ok()
+                    .map(HighScoresPayloadV2::from),
+                2 => postcard::from_bytes::<HighScoresPayloadV2>(&envelope.data).ok(),
                 v => {
                     eprintln!("high_scores: unknown version {v}");
                     None
                 }
             })
-            .unwrap_or_else(|| HighScoresPayloadV1 {
+            .unwrap_or_else(|| HighScoresPayloadV2 {
                 entries: HashMap::new(),
             });
 
         Self { payload, save_path }
     }
 
-    pub fn record(&mut self, grid_size: GridSize, difficulty: Difficulty, score: u32) {
-        let bucket = self
-            .payload
-            .entries
-            .entry((grid_size, difficulty))
-            .or_default();
+    pub fn record(&mut self, bucket: ScoreBucket, score: u32) {
+        let bucket = self.payload.entries.entry(bucket).or_default();
         bucket.push(HighScoreEntry { score });
         bucket.sort_unstable_by(|a, b| b.score.cmp(&a.score));
         bucket.truncate(MAX_ENTRIES);
         self.persist();
     }
 
-    pub fn get_scores_for(&self, grid_size: GridSize, difficulty: Difficulty) -> &[HighScoreEntry] {
+    pub fn get_scores_for(&self, bucket: ScoreBucket) -> &[HighScoreEntry] {
         self.payload
             .entries
-            .get(&(grid_size, difficulty))
+            .get(&bucket)
             .map(Vec::as_slice)
             .unwrap_or(&[])
     }
@@ -83,7 +122,7 @@ impl HighScores {
             }
         };
         let envelope = VersionedHighScores {
-            version: 1,
+            version: CURRENT_VERSION,
             data: inner,
         };
         match postcard::to_stdvec(&envelope) {
@@ -0,0 +1,85 @@
+use macroquad::{
+    color::{Color, WHITE},
+    material::{Material, MaterialParams, gl_use_default_material, gl_use_material, load_material},
+    math::{Rect, Vec2},
+    miniquad::{
+        BlendFactor, BlendState, BlendValue, Equation, PipelineParams, ShaderSource, UniformDesc,
+        UniformType,
+    },
+    shapes::draw_rectangle,
+};
+
+const VERTEX: &str = "#version 100
+attribute vec3 position;
+attribute vec2 texcoord;
+attribute vec4 color0;
+varying mediump vec2 world;
+uniform mat4 Model;
+uniform mat4 Projection;
+void main() {
+    gl_Position = Projection * Model * vec4(position, 1);
+    world = position.xy;
+}
+";
+
+const VIGNETTE_FRAG: &str = "#version 100
+precision mediump float;
+varying mediump vec2 world;
+uniform vec2 center;
+uniform float radius;
+uniform float falloff;
+uniform vec4 shade;
+void main() {
+    float fade = smoothstep(radius, radius + falloff, distance(world, center));
+    gl_FragColor = vec4(shade.rgb, shade.a * fade);
+}
+";
+
+/// Darkens everything outside a circle of light
+pub struct LanternPipeline {
+    material: Material,
+}
+
+impl LanternPipeline {
+    pub fn new() -> Self {
+        let material = load_material(
+            ShaderSource::Glsl {
+                vertex: VERTEX,
+                fragment: VIGNETTE_FRAG,
+            },
+            MaterialParams {
+                uniforms: vec![
+                    UniformDesc::new("center", UniformType::Float2),
+                    UniformDesc::new("radius", UniformType::Float1),
+                    UniformDesc::new("falloff", UniformType::Float1),
+                    UniformDesc::new("shade", UniformType::Float4),
+                ],
+                pipeline_params: PipelineParams {
+                    color_blend: Some(BlendState::new(
+                        Equation::Add,
+                        BlendFactor::Value(BlendValue::SourceAlpha),
+                        BlendFactor::OneMinusValue(BlendValue::SourceAlpha),
+                    )),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        )
+        .expect("Failed to load lantern vignette material");
+
+        Self { material }
+    }
+
+    /// Covers `area` in `shade`, fading in from the edge of a circle of `radius` around
+    /// `center` over a further `falloff` pixels.
+    pub fn apply(&self, area: Rect, center: Vec2, radius: f32, falloff: f32, shade: Color) {
+        gl_use_material(&self.material);
+        self.material.set_uniform("center", center.to_array());
+        self.material.set_uniform("radius", radius);
+        self.material.set_uniform("falloff", falloff);
+        self.material
+            .set_uniform("shade", shade.to_vec().to_array());
+        draw_rectangle(area.x, area.y, area.w, area.h, WHITE);
+        gl_use_default_material();
+    }
+}
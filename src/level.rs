@@ -0,0 +1,258 @@
+use strum::IntoEnumIterator;
+
+use crate::{
+    block::BlockType, board_shape::BoardShape, connectivity::Connectivity,
+    constants::editor::DEFAULT_STAR_SCORES, game_mode::GameMode, level_goal::LevelGoal,
+    level_id::LevelId, rules::GameRules, scoring::ScoringRule,
+};
+
+/// A handcrafted board with its own rules and goal.
+///
+/// Levels are written as `key: value` lines followed by the board, one row per line after a
+/// `grid:` line. Each block is the symbol of its `BlockType` and `.` is an empty cell. Lines
+/// starting with `#` are comments.
+///
+/// ```text
+/// name: First Light
+/// goal: clear              # or "score 500", or "clear-type ghost"
+/// mode: casual
+/// scoring: classic
+/// connectivity: orthogonal # optional
+/// shape: square            # optional
+/// stars: 1030 1050         # final scores needed for the second and third stars
+/// grid:
+/// pppb
+/// pbbb
+/// ```
+#[derive(Clone, PartialEq)]
+pub struct Level {
+    /// Where the level was loaded from, if it has been saved
+    pub id: Option<LevelId>,
+    pub name: String,
+    pub rules: GameRules,
+    pub goal: LevelGoal,
+    pub star_scores: [u32; 2],
+    pub rows: u32,
+    pub cols: u32,
+    /// Ordered row by row, top to bottom
+    cells: Vec<Option<BlockType>>,
+}
+
+impl Level {
+    /// An empty rows × cols board to clear under the default rules
+    pub fn new(name: &str, rows: u32, cols: u32) -> Self {
+        Self {
+            id: None,
+            name: name.to_string(),
+            rules: GameRules::default(),
+            goal: LevelGoal::ClearBoard,
+            star_scores: DEFAULT_STAR_SCORES,
+            rows,
+            cols,
+            cells: vec![None; (rows * cols) as usize],
+        }
+    }
+
+    pub fn parse(source: &str) -> Result<Self, String> {
+        let mut name = None;
+        let mut goal = None;
+        let mut star_scores = None;
+        let mut rules = GameRules::default();
+        let mut grid_rows: Vec<&str> = vec![];
+        let mut in_grid = false;
+
+        for (index, line) in source.lines().enumerate() {
+            let line_number = index + 1;
+            let line = line.split('#').next().unwrap_or_default().trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            if in_grid {
+                grid_rows.push(line);
+                continue;
+            }
+
+            let (key, value) = line
+                .split_once(':')
+                .ok_or_else(|| format!("line {line_number}: expected `key: value`"))?;
+            let value = value.trim();
+            let error = |message: String| format!("line {line_number}: {message}");
+            match key.trim() {
+                "name" => name = Some(value.to_string()),
+                "goal" => goal = Some(parse_goal(value).map_err(error)?),
+                "mode" => {
+                    rules.mode =
+                        parse_choice(value, GameMode::iter(), GameMode::label).map_err(error)?
+                }
+                "scoring" => {
+                    rules.scoring = parse_choice(value, ScoringRule::iter(), ScoringRule::label)
+                        .map_err(error)?
+                }
+                "connectivity" => {
+                    rules.connectivity =
+                        parse_choice(value, Connectivity::iter(), Connectivity::label)
+                            .map_err(error)?
+                }
+                "shape" => {
+                    rules.shape =
+                        parse_choice(value, BoardShape::iter(), BoardShape::label).map_err(error)?
+                }
+                "stars" => star_scores = Some(parse_star_scores(value).map_err(error)?),
+                "grid" => in_grid = true,
+                other => return Err(error(format!("unknown key `{other}`"))),
+            }
+        }
+
+        let name = name.ok_or("missing `name`")?;
+        let goal = goal.ok_or("missing `goal`")?;
+        let star_scores = star_scores.ok_or("missing `stars`")?;
+
+        let cols = grid_rows.first().ok_or("missing `grid`")?.chars().count();
+        let mut cells = vec![];
+        for row in &grid_rows {
+            if row.chars().count() != cols {
+                return Err("every grid row must be the same length".to_string());
+            }
+            for symbol in row.chars() {
+                cells.push(parse_cell(symbol)?);
+            }
+        }
+
+        Ok(Self {
+            id: None,
+            name,
+            rules,
+            goal,
+            star_scores,
+            rows: grid_rows.len() as u32,
+            cols: cols as u32,
+            cells,
+        })
+    }
+
+    pub fn block_type_at(&self, row: u32, col: u32) -> Option<BlockType> {
+        self.cells[(row * self.cols + col) as usize].clone()
+    }
+
+    pub fn set_block_type_at(&mut self, row: u32, col: u32, block_type: Option<BlockType>) {
+        self.cells[(row * self.cols + col) as usize] = block_type;
+    }
+
+    pub fn has_blocks(&self) -> bool {
+        self.cells.iter().any(Option::is_some)
+    }
+
+    /// Changes the size of the board, keeping the blocks in its top-left corner
+    pub fn resize(&mut self, rows: u32, cols: u32) {
+        let mut cells = vec![None; (rows * cols) as usize];
+        for row in 0..rows.min(self.rows) {
+            for col in 0..cols.min(self.cols) {
+                cells[(row * cols + col) as usize] = self.block_type_at(row, col);
+            }
+        }
+
+        self.rows = rows;
+        self.cols = cols;
+        self.cells = cells;
+    }
+
+    /// Writes the level in the format read by `parse`
+    pub fn to_source(&self) -> String {
+        let goal = match &self.goal {
+            LevelGoal::ClearBoard => "clear".to_string(),
+            LevelGoal::ReachScore(score) => format!("score {score}"),
+            LevelGoal::ClearType(block_type) => {
+                format!("clear-type {}", block_type.label().to_lowercase())
+            }
+        };
+        let mut lines = vec![
+            format!("name: {}", self.name),
+            format!("goal: {goal}"),
+            format!("mode: {}", self.rules.mode.label().to_lowercase()),
+            format!("scoring: {}", self.rules.scoring.label().to_lowercase()),
+            format!(
+                "connectivity: {}",
+                self.rules.connectivity.label().to_lowercase()
+            ),
+            format!("shape: {}", self.rules.shape.label().to_lowercase()),
+            format!("stars: {} {}", self.star_scores[0], self.star_scores[1]),
+            "grid:".to_string(),
+        ];
+        for row in 0..self.rows {
+            lines.push(
+                (0..self.cols)
+                    .map(|col| {
+                        self.block_type_at(row, col)
+                            .map_or('.', |block_type| block_type.symbol())
+                    })
+                    .collect(),
+            );
+        }
+
+        lines.join("\n") + "\n"
+    }
+
+    /// Stars earned for finishing with `score`: none unless the goal was met, then one more
+    /// for each star score reached
+    pub fn stars_for(&self, goal_met: bool, score: u32) -> u8 {
+        if !goal_met {
+            return 0;
+        }
+
+        1 + self
+            .star_scores
+            .iter()
+            .filter(|&&star_score| score >= star_score)
+            .count() as u8
+    }
+}
+
+/// Matches `value` against each choice's label, ignoring case
+fn parse_choice<T>(
+    value: &str,
+    choices: impl Iterator<Item = T>,
+    label: impl Fn(&T) -> &str,
+) -> Result<T, String> {
+    choices
+        .into_iter()
+        .find(|choice| label(choice).eq_ignore_ascii_case(value))
+        .ok_or_else(|| format!("unknown value `{value}`"))
+}
+
+fn parse_goal(value: &str) -> Result<LevelGoal, String> {
+    let (kind, argument) = value.split_once(' ').unwrap_or((value, ""));
+    match kind {
+        "clear" => Ok(LevelGoal::ClearBoard),
+        "score" => argument
+            .trim()
+            .parse()
+            .map(LevelGoal::ReachScore)
+            .map_err(|_| format!("invalid score `{argument}`")),
+        "clear-type" => parse_choice(argument.trim(), BlockType::all(), BlockType::label)
+            .map(LevelGoal::ClearType),
+        other => Err(format!("unknown goal `{other}`")),
+    }
+}
+
+fn parse_star_scores(value: &str) -> Result<[u32; 2], String> {
+    let scores = value
+        .split_whitespace()
+        .map(|score| score.parse::<u32>())
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|_| format!("invalid star scores `{value}`"))?;
+    scores
+        .try_into()
+        .map_err(|_| "expected two star scores".to_string())
+}
+
+fn parse_cell(symbol: char) -> Result<Option<BlockType>, String> {
+    if symbol == '.' {
+        return Ok(None);
+    }
+
+    BlockType::all()
+        .find(|block_type| block_type.symbol() == symbol)
+        .map(Some)
+        .ok_or_else(|| format!("unknown block `{symbol}`"))
+}
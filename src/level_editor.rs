@@ -0,0 +1,232 @@
+use std::collections::HashSet;
+use std::path::PathBuf;
+
+use macroquad::{
+    math::{Vec2, vec2},
+    rand::RandGenerator,
+};
+
+use crate::{
+    block::BlockType,
+    constants::editor::{DEFAULT_COLS, DEFAULT_ROWS, HISTORY_LIMIT, MAX_NAME_LENGTH},
+    coordinate::Coordinate,
+    editor_tool::EditorTool,
+    game_session::GameSession,
+    game_settings::GameSettings,
+    grid_layout::GridLayout,
+    level::Level,
+    level_goal::LevelGoal,
+    level_pack::save_custom_level,
+    level_setting::LevelSetting,
+};
+
+/// A level being painted in the editor, with the history needed to undo each change
+pub struct LevelEditor {
+    pub level: Level,
+    pub brush: BlockType,
+    pub tool: EditorTool,
+    /// The level laid out as a game board for drawing, rebuilt after every change
+    pub preview: GameSession,
+    /// Whether the level is being test-played
+    pub is_testing: bool,
+    /// Outcome of the last save, shown until the level next changes
+    pub status: Option<String>,
+    /// Where the level was last saved, which later saves may replace
+    saved_to: Option<PathBuf>,
+    history: Vec<Level>,
+    /// Whether the mouse button went down on the board and hasn't been released
+    stroke_in_progress: bool,
+    /// Whether the current stroke has already saved an undo step
+    stroke_recorded: bool,
+}
+
+impl LevelEditor {
+    pub fn new() -> Self {
+        let level = Level::new("Untitled", DEFAULT_ROWS, DEFAULT_COLS);
+        Self {
+            preview: LevelEditor::build_preview(&level, Vec2::ZERO, Vec2::ZERO),
+            level,
+            brush: BlockType::default(),
+            tool: EditorTool::default(),
+            is_testing: false,
+            status: None,
+            saved_to: None,
+            history: vec![],
+            stroke_in_progress: false,
+            stroke_recorded: false,
+        }
+    }
+
+    fn build_preview(level: &Level, position: Vec2, dimensions: Vec2) -> GameSession {
+        let settings = GameSettings {
+            rules: level.rules,
+            ..GameSettings::default()
+        };
+        let layout = GridLayout::from_level(position, dimensions, level);
+        GameSession::new(settings, layout, RandGenerator::new())
+    }
+
+    /// Lays the preview out at `position` with size `dimensions`
+    pub fn resize_preview(&mut self, position: Vec2, dimensions: Vec2) {
+        self.preview = LevelEditor::build_preview(&self.level, position, dimensions);
+    }
+
+    fn refresh_preview(&mut self) {
+        let layout = &self.preview.layout;
+        let position = vec2(layout.x(), layout.y());
+        let dimensions = vec2(layout.width(), layout.height());
+        self.resize_preview(position, dimensions);
+    }
+
+    pub fn grid_extent(&self) -> Vec2 {
+        self.level
+            .rules
+            .shape
+            .extent(self.level.rows, self.level.cols)
+    }
+
+    /// Cells a click at `position` would paint with the current tool
+    pub fn affected_cells(&self, position: Coordinate) -> HashSet<Coordinate> {
+        match self.tool {
+            EditorTool::Fill => self.fill_region(position),
+            EditorTool::Paint | EditorTool::Erase => HashSet::from([position]),
+        }
+    }
+
+    /// The cell at `start` and every cell connected to it holding the same block, or
+    /// connected empty cells if it is empty
+    fn fill_region(&self, start: Coordinate) -> HashSet<Coordinate> {
+        let target = self.level.block_type_at(start.row, start.col);
+        let mut region = HashSet::new();
+        let mut frontier = vec![start];
+        while let Some(position) = frontier.pop() {
+            if !region.contains(&position)
+                && self.level.block_type_at(position.row, position.col) == target
+            {
+                frontier.extend(self.preview.layout.get_neighbors(position));
+                region.insert(position);
+            }
+        }
+
+        region
+    }
+
+    /// Applies the current tool at `position`. Dragging keeps painting until the mouse button
+    /// is released, and the whole stroke is undone in one step.
+    pub fn apply_tool(&mut self, position: Coordinate, starts_stroke: bool) {
+        if starts_stroke {
+            self.stroke_in_progress = true;
+            self.stroke_recorded = false;
+        } else if !self.stroke_in_progress || self.tool == EditorTool::Fill {
+            return;
+        }
+
+        let block_type = match self.tool {
+            EditorTool::Paint | EditorTool::Fill => Some(self.brush.clone()),
+            EditorTool::Erase => None,
+        };
+        let before = self.level.clone();
+        for cell in self.affected_cells(position) {
+            self.level
+                .set_block_type_at(cell.row, cell.col, block_type.clone());
+        }
+
+        if self.level != before {
+            if !self.stroke_recorded {
+                self.record_history(before);
+                self.stroke_recorded = true;
+            }
+            self.status = None;
+            self.refresh_preview();
+        }
+    }
+
+    pub fn end_stroke(&mut self) {
+        self.stroke_in_progress = false;
+    }
+
+    /// Makes a change to the level that can be undone
+    pub fn edit(&mut self, change: impl FnOnce(&mut Level)) {
+        let before = self.level.clone();
+        change(&mut self.level);
+        if self.level != before {
+            self.record_history(before);
+            self.status = None;
+            self.refresh_preview();
+        }
+    }
+
+    pub fn adjust(&mut self, setting: LevelSetting, steps: i32) {
+        self.edit(|level| match setting {
+            LevelSetting::Rows => level.resize(setting.adjust(level.rows, steps), level.cols),
+            LevelSetting::Cols => level.resize(level.rows, setting.adjust(level.cols, steps)),
+            LevelSetting::TargetScore => {
+                if let LevelGoal::ReachScore(target) = &mut level.goal {
+                    *target = setting.adjust(*target, steps);
+                }
+            }
+            LevelSetting::SecondStar => {
+                level.star_scores[0] = setting.adjust(level.star_scores[0], steps)
+            }
+            LevelSetting::ThirdStar => {
+                level.star_scores[1] = setting.adjust(level.star_scores[1], steps)
+            }
+        });
+    }
+
+    /// Adds a typed character to the level's name. Characters with a meaning in level files
+    /// are ignored.
+    pub fn type_name_char(&mut self, c: char) {
+        if c.is_control() || c == '#' || self.level.name.chars().count() >= MAX_NAME_LENGTH {
+            return;
+        }
+
+        self.edit(|level| level.name.push(c));
+    }
+
+    pub fn erase_name_char(&mut self) {
+        self.edit(|level| {
+            level.name.pop();
+        });
+    }
+
+    fn record_history(&mut self, level: Level) {
+        if self.history.len() == HISTORY_LIMIT {
+            self.history.remove(0);
+        }
+        self.history.push(level);
+    }
+
+    pub fn undo(&mut self) {
+        if let Some(level) = self.history.pop() {
+            self.level = level;
+            self.status = None;
+            self.refresh_preview();
+        }
+    }
+
+    /// Checks the level can be played, describing what is missing if not
+    pub fn validate(&self) -> Result<(), String> {
+        if self.level.name.trim().is_empty() {
+            return Err("Name the level first".to_string());
+        }
+        if !self.level.has_blocks() {
+            return Err("Paint some blocks first".to_string());
+        }
+        Ok(())
+    }
+
+    pub fn save(&mut self) {
+        let result = self
+            .validate()
+            .and_then(|()| save_custom_level(&self.level, self.saved_to.as_deref()));
+        self.status = Some(match result {
+            Ok(path) => {
+                let status = format!("Saved to {}", path.display());
+                self.saved_to = Some(path);
+                status
+            }
+            Err(e) => e,
+        });
+    }
+}
@@ -0,0 +1,38 @@
+use num_format::{Locale, ToFormattedString};
+
+use crate::{block::BlockType, grid_layout::GridLayout};
+
+/// What the player must achieve to complete a level
+#[derive(Clone, PartialEq)]
+pub enum LevelGoal {
+    ClearBoard,
+    ReachScore(u32),
+    /// Clear every block of one type; the rest of the board may stay
+    ClearType(BlockType),
+}
+
+impl LevelGoal {
+    pub fn description(&self) -> String {
+        match self {
+            LevelGoal::ClearBoard => "Clear the board".to_string(),
+            LevelGoal::ReachScore(score) => {
+                format!("Score {}", score.to_formatted_string(&Locale::en))
+            }
+            LevelGoal::ClearType(block_type) => format!("Clear every {}", block_type.label()),
+        }
+    }
+
+    pub fn is_met(&self, layout: &GridLayout, score: u32) -> bool {
+        match self {
+            LevelGoal::ClearBoard => layout.blocks_remaining == 0,
+            LevelGoal::ReachScore(target) => score >= *target,
+            LevelGoal::ClearType(block_type) => !layout.contains_type(block_type),
+        }
+    }
+
+    /// Whether meeting the goal ends the level straight away. Score goals are only judged
+    /// once the final score is known.
+    pub fn ends_when_met(&self) -> bool {
+        !matches!(self, LevelGoal::ReachScore(_))
+    }
+}
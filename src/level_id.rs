@@ -0,0 +1,9 @@
+/// Where a level came from and the name of its file, which stays the same between runs.
+/// Levels with the same name from different places keep their own progress.
+#[derive(Clone, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+pub enum LevelId {
+    /// One of the levels bundled with the game
+    Starter(String),
+    /// A level saved by the level editor
+    Custom(String),
+}
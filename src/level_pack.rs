@@ -0,0 +1,130 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use directories::ProjectDirs;
+
+use crate::{level::Level, level_id::LevelId};
+
+/// Folder in the game's data directory that the level editor saves into
+const CUSTOM_LEVELS_DIR: &str = "levels";
+
+/// Levels bundled with the game, in the order they are played
+const STARTER_PACK: [(&str, &str); 6] = [
+    (
+        "01_first_light",
+        include_str!("../assets/levels/01_first_light.txt"),
+    ),
+    (
+        "02_pairs_in_the_dark",
+        include_str!("../assets/levels/02_pairs_in_the_dark.txt"),
+    ),
+    (
+        "03_ghost_hunt",
+        include_str!("../assets/levels/03_ghost_hunt.txt"),
+    ),
+    (
+        "04_blood_money",
+        include_str!("../assets/levels/04_blood_money.txt"),
+    ),
+    (
+        "05_corner_to_corner",
+        include_str!("../assets/levels/05_corner_to_corner.txt"),
+    ),
+    (
+        "06_wrapped_in_gauze",
+        include_str!("../assets/levels/06_wrapped_in_gauze.txt"),
+    ),
+];
+
+/// Parses the bundled levels, skipping any that fail to parse
+pub fn load_starter_pack() -> Vec<Level> {
+    STARTER_PACK
+        .iter()
+        .filter_map(|(file, source)| match Level::parse(source) {
+            Ok(mut level) => {
+                level.id = Some(LevelId::Starter(file.to_string()));
+                Some(level)
+            }
+            Err(e) => {
+                eprintln!("levels: {file}: {e}");
+                None
+            }
+        })
+        .collect()
+}
+
+/// Parses the levels saved by the level editor, in file name order
+pub fn load_custom_levels() -> Vec<Level> {
+    let Some(entries) = custom_levels_dir().and_then(|dir| fs::read_dir(dir).ok()) else {
+        return vec![];
+    };
+
+    let mut paths: Vec<PathBuf> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "txt"))
+        .collect();
+    paths.sort();
+
+    paths
+        .iter()
+        .filter_map(|path| {
+            let source = fs::read_to_string(path).ok()?;
+            let stem = path.file_stem()?.to_string_lossy().into_owned();
+            match Level::parse(&source) {
+                Ok(mut level) => {
+                    level.id = Some(LevelId::Custom(stem));
+                    Some(level)
+                }
+                Err(e) => {
+                    eprintln!("levels: {}: {e}", path.display());
+                    None
+                }
+            }
+        })
+        .collect()
+}
+
+/// Writes `level` to the custom levels folder in a file named after it. A saved level is
+/// only replaced if its file is `replacing`, where this level was saved before, so names
+/// that would share a file with a starter level or another saved level are refused.
+/// Returns where it was saved.
+pub fn save_custom_level(level: &Level, replacing: Option<&Path>) -> Result<PathBuf, String> {
+    let stem = file_stem(&level.name);
+    let starter_names = load_starter_pack().into_iter().map(|level| level.name);
+    if starter_names
+        .map(|name| file_stem(&name))
+        .any(|s| s == stem)
+    {
+        return Err("A starter level already has that name".to_string());
+    }
+
+    let dir = custom_levels_dir().ok_or("no data directory")?;
+    fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    let path = dir.join(format!("{stem}.txt"));
+    if path.exists() && replacing != Some(path.as_path()) {
+        return Err("Another saved level already has that name".to_string());
+    }
+    fs::write(&path, level.to_source()).map_err(|e| e.to_string())?;
+    Ok(path)
+}
+
+fn custom_levels_dir() -> Option<PathBuf> {
+    ProjectDirs::from("com", "xapphire13", "bleak-blocks")
+        .map(|dirs| dirs.data_dir().join(CUSTOM_LEVELS_DIR))
+}
+
+/// File name for a level, e.g. "Wrapped in Gauze" becomes "wrapped_in_gauze"
+fn file_stem(name: &str) -> String {
+    let stem = name
+        .to_lowercase()
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join("_");
+    if stem.is_empty() {
+        "untitled".to_string()
+    } else {
+        stem
+    }
+}
@@ -0,0 +1,127 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+
+use directories::ProjectDirs;
+use serde::{Deserialize, Serialize};
+
+use crate::{level::Level, level_id::LevelId};
+
+const SAVE_FILE: &str = "level_progress.bin";
+const CURRENT_VERSION: u8 = 2;
+
+#[derive(Serialize, Deserialize)]
+struct LevelProgressPayloadV1 {
+    /// Best star rating for each level, keyed by level name
+    stars: HashMap<String, u8>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct LevelProgressPayloadV2 {
+    stars: HashMap<LevelId, u8>,
+}
+
+impl LevelProgressPayloadV2 {
+    /// Ratings before V2 were shared by every level with the same name, so each of those
+    /// levels keeps the rating
+    fn migrate(v1: LevelProgressPayloadV1, levels: &[Level]) -> Self {
+        Self {
+            stars: levels
+                .iter()
+                .filter_map(|level| {
+                    let stars = v1.stars.get(&level.name)?;
+                    Some((level.id.clone()?, *stars))
+                })
+                .collect(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct VersionedLevelProgress {
+    version: u8,
+    data: Vec<u8>,
+}
+
+/// The player's best star rating on each level
+pub struct LevelProgress {
+    payload: LevelProgressPayloadV2,
+    save_path: Option<PathBuf>,
+}
+
+impl LevelProgress {
+    /// Loads the saved ratings. `levels` are every level that can be played, which older
+    /// saves are matched to by name.
+    pub fn load(levels: &[Level]) -> Self {
+        let save_path = Self::resolve_save_path();
+        let payload = save_path
+            .as_ref()
+            .and_then(|p| fs::read(p).ok())
+            .and_then(|bytes| postcard::from_bytes::<VersionedLevelProgress>(&bytes).ok())
+            .and_then(|envelope| match envelope.version {
+                1 => postcard::from_bytes::<LevelProgressPayloadV1>(&envelope.data)
+                    .ok()
+                    .map(|v1| LevelProgressPayloadV2::migrate(v1, levels)),
+                2 => postcard::from_bytes::<LevelProgressPayloadV2>(&envelope.data).ok(),
+                v => {
+                    eprintln!("level_progress: unknown version {v}");
+                    None
+                }
+            })
+            .unwrap_or_else(|| LevelProgressPayloadV2 {
+                stars: HashMap::new(),
+            });
+
+        Self { payload, save_path }
+    }
+
+    pub fn stars_for(&self, level: &Level) -> u8 {
+        level
+            .id
+            .as_ref()
+            .and_then(|id| self.payload.stars.get(id))
+            .copied()
+            .unwrap_or(0)
+    }
+
+    /// Keeps the best rating earned on the level. Levels that haven't been saved have
+    /// nowhere to keep one.
+    pub fn record(&mut self, level: &Level, stars: u8) {
+        let Some(id) = &level.id else { return };
+        if stars <= self.stars_for(level) {
+            return;
+        }
+
+        self.payload.stars.insert(id.clone(), stars);
+        self.persist();
+    }
+
+    fn persist(&self) {
+        let Some(path) = &self.save_path else { return };
+        let inner = match postcard::to_stdvec(&self.payload) {
+            Ok(v) => v,
+            Err(e) => {
+                eprintln!("level_progress: serialize payload: {e}");
+                return;
+            }
+        };
+        let envelope = VersionedLevelProgress {
+            version: CURRENT_VERSION,
+            data: inner,
+        };
+        match postcard::to_stdvec(&envelope) {
+            Ok(bytes) => {
+                let _ = fs::write(path, bytes);
+            }
+            Err(e) => eprintln!("level_progress: serialize envelope: {e}"),
+        }
+    }
+
+    fn resolve_save_path() -> Option<PathBuf> {
+        ProjectDirs::from("com", "xapphire13", "bleak-blocks").map(|dirs| {
+            let data_dir = dirs.data_dir().to_path_buf();
+            let _ = fs::create_dir_all(&data_dir);
+            data_dir.join(SAVE_FILE)
+        })
+    }
+}
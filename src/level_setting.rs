@@ -0,0 +1,25 @@
+use crate::constants::editor::{MAX_BOARD_SIZE, MIN_BOARD_SIZE, SCORE_STEP};
+
+/// A number on a level that the editor steps up and down
+#[derive(Copy, Clone, PartialEq, Eq)]
+pub enum LevelSetting {
+    Rows,
+    Cols,
+    TargetScore,
+    SecondStar,
+    ThirdStar,
+}
+
+impl LevelSetting {
+    /// Applies `steps` presses of the setting's button to `value`, keeping it in range
+    pub fn adjust(&self, value: u32, steps: i32) -> u32 {
+        match self {
+            LevelSetting::Rows | LevelSetting::Cols => value
+                .saturating_add_signed(steps)
+                .clamp(MIN_BOARD_SIZE, MAX_BOARD_SIZE),
+            LevelSetting::TargetScore | LevelSetting::SecondStar | LevelSetting::ThirdStar => {
+                value.saturating_add_signed(steps * SCORE_STEP as i32)
+            }
+        }
+    }
+}
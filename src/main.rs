@@ -6,20 +6,53 @@ use crate::{app::App, fps_limiter::FpsLimiter};
 
 mod app;
 mod block;
+mod block_move;
+mod block_registry;
 mod blur;
+mod board_depth;
+mod board_shape;
+mod board_style;
+mod clear_effect;
+mod clearable_board;
+mod cleared_group;
+mod confirmation;
+mod connectivity;
 mod constants;
 mod coordinate;
 mod difficulty;
 mod drawing;
+mod easing;
+mod editor_tool;
+mod fixed_timestep;
 mod fps_limiter;
+mod game_mode;
 mod game_session;
+mod game_settings;
 mod game_ui;
+mod generation_strategy;
+mod gravity;
 mod grid_layout;
 mod grid_size;
 mod high_scores;
+mod lantern;
+mod level;
+mod level_editor;
+mod level_goal;
+mod level_id;
+mod level_pack;
+mod level_progress;
+mod level_setting;
 mod orientation;
+mod particle_system;
 mod physics_system;
+mod rules;
+mod score_bucket;
+mod score_popups;
+mod scoring;
+mod settle_mode;
 mod sprite_sheet;
+mod transition;
+mod transition_kind;
 mod window_chrome;
 
 fn window_conf() -> Conf {
@@ -51,6 +84,9 @@ async fn main() {
         let (input_event, frame_state) = game.handle_input();
         game.update(input_event);
         game.render(frame_state);
+        if game.should_quit() {
+            break;
+        }
 
         fps_limiter.wait_for_next_frame();
         next_frame().await
@@ -1,3 +1,5 @@
+use macroquad::math::Vec2;
+
 #[derive(Copy, Clone, PartialEq, Default)]
 pub enum Orientation {
     Portrait,
@@ -12,4 +14,16 @@ impl Orientation {
             Orientation::Landscape => "Landscape",
         }
     }
+
+    /// The orientation a board of `grid_extent` is laid out in. Custom sizes can be taller
+    /// or wider than the chosen orientation suggests, and square boards keep it.
+    pub fn fitting(self, grid_extent: Vec2) -> Orientation {
+        if grid_extent.x > grid_extent.y {
+            Orientation::Landscape
+        } else if grid_extent.y > grid_extent.x {
+            Orientation::Portrait
+        } else {
+            self
+        }
+    }
 }
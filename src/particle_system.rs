@@ -0,0 +1,179 @@
+use macroquad::{
+    color::{Color, WHITE},
+    math::{Vec2, vec2},
+    rand::gen_range,
+    shapes::draw_circle,
+};
+
+use crate::{
+    block::BlockType,
+    clear_effect::ClearEffect,
+    constants::{
+        particles::{
+            DISSOLVE_DURATION, GRAVITY, LIFETIME, MAX_ALIVE, MAX_PER_CLEAR, PER_BLOCK,
+            POP_DURATION, POP_GROWTH, SIZE, SPEED,
+        },
+        ui::BLOCK_CORNER_RADIUS_FACTOR,
+    },
+    drawing::draw_rounded_rect,
+    sprite_sheet::SpriteSheet,
+};
+
+/// Effects played where blocks were cleared. Sizes and speeds are relative to the block
+/// size, so effects look the same on every board.
+pub struct ParticleSystem {
+    particles: Vec<Particle>,
+    fading_blocks: Vec<FadingBlock>,
+}
+
+struct Particle {
+    position: Vec2,
+    velocity: Vec2,
+    color: Color,
+    radius: f32,
+    /// Seconds since the particle was spawned
+    age: f32,
+    lifetime: f32,
+}
+
+/// A cleared block still drawn while it pops or dissolves
+struct FadingBlock {
+    block_type: BlockType,
+    center: Vec2,
+    size: f32,
+    effect: ClearEffect,
+    age: f32,
+}
+
+impl FadingBlock {
+    fn duration(&self) -> f32 {
+        match self.effect {
+            ClearEffect::Dissolve => DISSOLVE_DURATION,
+            _ => POP_DURATION,
+        }
+    }
+}
+
+impl ParticleSystem {
+    pub fn new() -> Self {
+        Self {
+            particles: vec![],
+            fading_blocks: vec![],
+        }
+    }
+
+    /// Plays `effect` for a group of blocks cleared at once, given as the center of each
+    /// block's cell and its type. Bigger groups throw out more particles, up to
+    /// `MAX_PER_CLEAR`.
+    pub fn spawn_clear(
+        &mut self,
+        effect: ClearEffect,
+        blocks: &[(Vec2, BlockType)],
+        block_size: f32,
+    ) {
+        if effect == ClearEffect::Off || blocks.is_empty() {
+            return;
+        }
+
+        for (center, block_type) in blocks {
+            self.fading_blocks.push(FadingBlock {
+                block_type: block_type.clone(),
+                center: *center,
+                size: block_size,
+                effect,
+                age: 0.0,
+            });
+        }
+
+        if effect != ClearEffect::Burst {
+            return;
+        }
+
+        // Share the particles out evenly when a large group hits the cap
+        let count = (blocks.len() * PER_BLOCK)
+            .min(MAX_PER_CLEAR)
+            .min(MAX_ALIVE.saturating_sub(self.particles.len()));
+        for i in 0..count {
+            let (center, block_type) = &blocks[i % blocks.len()];
+            let angle = gen_range(0.0, std::f32::consts::TAU);
+            let speed = gen_range(SPEED.0, SPEED.1) * block_size;
+            self.particles.push(Particle {
+                position: *center,
+                velocity: vec2(angle.cos(), angle.sin()) * speed,
+                color: block_type.get_color(),
+                radius: gen_range(SIZE.0, SIZE.1) * block_size,
+                age: 0.0,
+                lifetime: gen_range(LIFETIME.0, LIFETIME.1),
+            });
+        }
+    }
+
+    pub fn update(&mut self, time_delta: f32) {
+        for particle in &mut self.particles {
+            particle.velocity.y += GRAVITY * particle.radius * time_delta;
+            particle.position += particle.velocity * time_delta;
+            particle.age += time_delta;
+        }
+        self.particles
+            .retain(|particle| particle.age < particle.lifetime);
+
+        for block in &mut self.fading_blocks {
+            block.age += time_delta;
+        }
+        self.fading_blocks
+            .retain(|block| block.age < block.duration());
+    }
+
+    /// Drops every effect still playing, such as when the board is resized or a new game
+    /// starts
+    pub fn clear(&mut self) {
+        self.particles.clear();
+        self.fading_blocks.clear();
+    }
+
+    pub fn render(&self, sprite_sheet: &SpriteSheet) {
+        for block in &self.fading_blocks {
+            let t = block.age / block.duration();
+            let (scale, alpha) = match block.effect {
+                // Swells a little, then shrinks away
+                ClearEffect::Burst => (
+                    (1.0 + POP_GROWTH * (t * std::f32::consts::PI).sin()) * (1.0 - t * t),
+                    1.0,
+                ),
+                _ => (1.0 - 0.2 * t, 1.0 - t),
+            };
+            let size = block.size * scale;
+            let position = block.center - Vec2::splat(size / 2.0);
+            let mut color = block.block_type.get_color();
+            color.a *= alpha;
+            draw_rounded_rect(
+                position.x,
+                position.y,
+                size,
+                size,
+                size * BLOCK_CORNER_RADIUS_FACTOR,
+                color,
+            );
+            sprite_sheet.render_sprite(
+                block.block_type.get_sprite_id(),
+                position,
+                size,
+                Color { a: alpha, ..WHITE },
+            );
+        }
+
+        for particle in &self.particles {
+            let life_left = 1.0 - particle.age / particle.lifetime;
+            let color = Color {
+                a: particle.color.a * life_left,
+                ..particle.color
+            };
+            draw_circle(
+                particle.position.x,
+                particle.position.y,
+                particle.radius * (0.5 + 0.5 * life_left),
+                color,
+            );
+        }
+    }
+}
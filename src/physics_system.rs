@@ -1,100 +1,233 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 use macroquad::math::Vec2;
 
-use crate::{coordinate::Coordinate, grid_layout::GridLayout};
+use crate::{
+    block_move::BlockMove,
+    constants::physics::{BOUNCE_DURATION, BOUNCE_HEIGHT, FAST_SETTLE_DURATION, STAGGER},
+    coordinate::Coordinate,
+    easing::Easing,
+    grid_layout::GridLayout,
+    settle_mode::SettleMode,
+};
 
 pub struct PhysicsSystem {
-    animating_blocks: HashMap<Coordinate, AnimationState>,
-    velocity: Vec2,
+    /// Keyed by the cell and layer each block started from
+    animating_blocks: HashMap<(Coordinate, u32), AnimationState>,
+    easing: Easing,
+    landing_bounce: bool,
+    settle_mode: SettleMode,
 }
 
 struct AnimationState {
     target: Coordinate,
+    /// Corner the block turns at on its way to `target`, if it takes an L-shaped path
+    via: Option<Coordinate>,
     offset: Vec2, // Current offset from grid position
+    /// Offset as of the previous step, for drawing frames that fall between steps
+    previous_offset: Vec2,
+    /// Seconds left before the block starts moving
+    delay: f32,
+    /// Seconds since the block started moving
+    elapsed: f32,
+    /// Seconds the block takes to reach its target, set once it starts moving
+    duration: Option<f32>,
+    /// Whether the block has reached its target and finished bouncing
     completed: bool,
 }
 
 impl PhysicsSystem {
-    pub fn new() -> Self {
+    pub fn new(easing: Easing, landing_bounce: bool, settle_mode: SettleMode) -> Self {
         Self {
             animating_blocks: HashMap::new(),
-            velocity: Vec2::ZERO,
+            easing,
+            landing_bounce,
+            settle_mode,
         }
     }
 
-    pub fn queue_block_animation(&mut self, from: Coordinate, to: Coordinate) {
-        self.animating_blocks.insert(
-            from,
-            AnimationState {
-                target: to,
-                offset: Vec2::ZERO,
-                completed: false,
-            },
-        );
+    /// Queues a batch of moves. In each line of moving blocks the one nearest where they are
+    /// heading starts first and the rest follow one by one, so lines peel away instead of
+    /// moving as a slab. In the fast settle mode every block starts at once.
+    pub fn queue_block_animations(&mut self, moves: &[BlockMove]) {
+        let direction = |block_move: &BlockMove| {
+            (
+                (block_move.to.row as i64 - block_move.from.row as i64).signum(),
+                (block_move.to.col as i64 - block_move.from.col as i64).signum(),
+            )
+        };
+        // Blocks in the same column when falling, or the same row when shifting
+        let line = |block_move: &BlockMove| {
+            let direction = direction(block_move);
+            let across = if direction.1 == 0 {
+                block_move.from.col
+            } else {
+                block_move.from.row
+            };
+            (direction, across)
+        };
+        // How far along its direction of travel each block starts
+        let lead = |block_move: &BlockMove| {
+            let (row_step, col_step) = direction(block_move);
+            block_move.from.row as i64 * row_step + block_move.from.col as i64 * col_step
+        };
+
+        let mut fronts: HashMap<((i64, i64), u32), i64> = HashMap::new();
+        for block_move in moves {
+            let lead = lead(block_move);
+            fronts
+                .entry(line(block_move))
+                .and_modify(|front| *front = (*front).max(lead))
+                .or_insert(lead);
+        }
+
+        for block_move in moves {
+            let cells_behind = match self.settle_mode {
+                SettleMode::Fast => 0,
+                _ => fronts[&line(block_move)] - lead(block_move),
+            };
+            self.animating_blocks.insert(
+                (block_move.from, block_move.layer),
+                AnimationState {
+                    target: block_move.to,
+                    via: block_move.via.filter(|_| self.settle_mode.turns_corners()),
+                    offset: Vec2::ZERO,
+                    previous_offset: Vec2::ZERO,
+                    delay: cells_behind as f32 * STAGGER,
+                    elapsed: 0.0,
+                    duration: None,
+                    completed: false,
+                },
+            );
+        }
     }
 
-    /// Updates all animating blocks
+    /// Updates all animating blocks. Each block moves along its own path to its target,
+    /// taking as long as it would accelerating from rest at the strength of `force`, so
+    /// blocks converging from opposite sides or shifting between staggered hex columns can
+    /// share one force. In the fast settle mode every move takes the same time instead.
+    /// Blocks settle into the grid as soon as they arrive. `time_delta` must be a fixed step
+    /// so every frame rate sees the same motion.
     /// Returns true if animations are still in progress
     pub fn update(&mut self, layout: &mut GridLayout, force: Vec2, time_delta: f32) -> bool {
         if self.animating_blocks.is_empty() {
             return false;
         }
 
-        self.velocity += force * time_delta;
-
-        self.update_block_positions(layout, time_delta);
+        self.update_block_positions(layout, force.length(), time_delta);
         self.finalize_completed_animations(layout);
 
         !self.animating_blocks.is_empty()
     }
 
-    fn update_block_positions(&mut self, layout: &mut GridLayout, time_delta: f32) {
-        let displacement = self.velocity * time_delta;
+    fn update_block_positions(&mut self, layout: &GridLayout, acceleration: f32, time_delta: f32) {
+        for ((from, _), animation_state) in &mut self.animating_blocks {
+            animation_state.previous_offset = animation_state.offset;
+            if animation_state.completed {
+                continue;
+            }
 
-        for (from, animation_state) in &mut self.animating_blocks {
-            animation_state.offset += displacement;
+            let mut time_delta = time_delta;
+            if animation_state.delay > 0.0 {
+                animation_state.delay -= time_delta;
+                if animation_state.delay > 0.0 {
+                    continue;
+                }
+                // Move for whatever is left of the step
+                time_delta = -animation_state.delay;
+            }
 
             let start_pos = layout.grid_to_world(*from);
             let target_pos = layout.grid_to_world(animation_state.target);
-            let total_distance = target_pos - start_pos;
-
-            if animation_state.offset.length() >= total_distance.length() {
-                animation_state.offset = total_distance;
+            let corner = animation_state
+                .via
+                .map_or(start_pos, |via| layout.grid_to_world(via));
+            // Offsets of the corner and the target from the start
+            let (to_corner, path) = (corner - start_pos, target_pos - start_pos);
+            let length = to_corner.length() + (path - to_corner).length();
+            let duration =
+                *animation_state
+                    .duration
+                    .get_or_insert_with(|| match self.settle_mode {
+                        SettleMode::Fast => FAST_SETTLE_DURATION,
+                        _ => (2.0 * length / acceleration).sqrt(),
+                    });
+
+            animation_state.elapsed += time_delta;
+            let travelled = length * self.easing.apply(animation_state.elapsed / duration);
+            animation_state.offset = if travelled < to_corner.length() {
+                to_corner.normalize_or_zero() * travelled
+            } else {
+                to_corner
+                    + (path - to_corner).normalize_or_zero() * (travelled - to_corner.length())
+            };
+
+            let landed_for = animation_state.elapsed - duration;
+            if landed_for < 0.0 {
+                continue;
+            }
+            if !self.landing_bounce || landed_for >= BOUNCE_DURATION {
+                animation_state.offset = path;
                 animation_state.completed = true;
+            } else {
+                // A small hop back the way the block came
+                let hop = (landed_for / BOUNCE_DURATION * std::f32::consts::PI).sin();
+                let back = (to_corner - path).normalize_or_zero();
+                animation_state.offset = path + back * hop * BOUNCE_HEIGHT * layout.block_size;
             }
         }
     }
 
+    /// Moves each block that has arrived into its target cell, once any block still
+    /// animating out of that cell has left it
     fn finalize_completed_animations(&mut self, layout: &mut GridLayout) {
-        if self
-            .animating_blocks
-            .iter()
-            .all(|(_, state)| state.completed)
-        {
-            // All animations complete - reset velocity and finalize positions
-            self.velocity = Vec2::ZERO;
-
-            // Remove blocks from original grid positions, then put them all into their new grid positions
-            for (block, to) in self
+        loop {
+            let sources: HashSet<(Coordinate, u32)> =
+                self.animating_blocks.keys().copied().collect();
+            let ready: Vec<(Coordinate, u32)> = self
                 .animating_blocks
-                .drain()
-                .flat_map(|(current_pos, animation_state)| {
-                    layout
-                        .take_block(current_pos)
-                        .map(|block| (block, animation_state.target))
+                .iter()
+                .filter(|((_, layer), state)| {
+                    state.completed && !sources.contains(&(state.target, *layer))
                 })
-                .collect::<Vec<_>>()
-            {
-                layout.place_block(to, block);
+                .map(|(key, _)| *key)
+                .collect();
+            if ready.is_empty() {
+                break;
+            }
+
+            for (from, layer) in ready {
+                let Some(animation_state) = self.animating_blocks.remove(&(from, layer)) else {
+                    continue;
+                };
+                if let Some(block) = layout.take_block_at(from, layer) {
+                    layout.place_block_at(animation_state.target, layer, block);
+                }
             }
         }
     }
 
-    pub fn get_animation_offset(&self, coord: Coordinate) -> Vec2 {
+    /// The block drawn at `point`, as the cell it is leaving and the cell it is heading to
+    pub fn block_at(&self, layout: &GridLayout, point: Vec2) -> Option<(Coordinate, Coordinate)> {
+        self.animating_blocks
+            .iter()
+            .find(|((from, _), state)| layout.world_to_grid(point - state.offset) == Some(*from))
+            .map(|((from, _), state)| (*from, state.target))
+    }
+
+    /// Whether any block is still animating out of `position`
+    pub fn is_moving_from(&self, position: Coordinate) -> bool {
+        self.animating_blocks
+            .keys()
+            .any(|(from, _)| *from == position)
+    }
+
+    /// Where a block is drawn relative to its cell, `alpha` of the way from the previous
+    /// step to the latest one
+    pub fn get_animation_offset(&self, coord: Coordinate, layer: u32, alpha: f32) -> Vec2 {
         self.animating_blocks
-            .get(&coord)
-            .map(|anim| anim.offset)
+            .get(&(coord, layer))
+            .map(|anim| anim.previous_offset.lerp(anim.offset, alpha))
             .unwrap_or(Vec2::ZERO)
     }
 }
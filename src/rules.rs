@@ -0,0 +1,74 @@
+use crate::{
+    board_depth::BoardDepth, board_shape::BoardShape, board_style::BoardStyle,
+    connectivity::Connectivity, game_mode::GameMode, scoring::ScoringRule,
+};
+
+/// Everything that changes how a game is played and scored. Scores are only comparable
+/// between games played under the same rules, so this is part of the high score bucket key.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Default, serde::Serialize, serde::Deserialize)]
+pub struct GameRules {
+    pub mode: GameMode,
+    pub scoring: ScoringRule,
+    pub connectivity: Connectivity,
+    pub shape: BoardShape,
+    pub depth: BoardDepth,
+    /// Whether some blocks are generated haunted
+    pub haunted: bool,
+    /// Whether boards are generated so that they can always be cleared completely
+    pub guaranteed_clear: bool,
+    /// How the blocks of random boards are dealt
+    pub style: BoardStyle,
+}
+
+impl GameRules {
+    /// Whether boards can be generated to clear completely under these rules. Any board can
+    /// be cleared when single blocks can be, and only a single layer is generated that way.
+    /// Cascades and haunted blocks change the board in ways the plan can't account for.
+    pub fn supports_guaranteed_clear(&self) -> bool {
+        self.depth.layers() == 1
+            && self.mode.min_group_size() > 1
+            && self.mode.cascade_group_size().is_none()
+            && !self.haunted
+    }
+
+    /// These rules on a board of `shape`, falling back to orthogonal connectivity if the
+    /// current one has no effect there
+    pub fn with_shape(self, shape: BoardShape) -> GameRules {
+        let connectivity = if self.connectivity.applies_to(shape) {
+            self.connectivity
+        } else {
+            Connectivity::Orthogonal
+        };
+        GameRules {
+            shape,
+            connectivity,
+            ..self
+        }
+    }
+
+    /// Short description of the rules, e.g. "Casual · Classic · Orthogonal · Square · Single"
+    pub fn summary(&self) -> String {
+        let summary = format!(
+            "{} · {} · {} · {} · {}",
+            self.mode.label(),
+            self.scoring.label(),
+            self.connectivity.label(),
+            self.shape.label(),
+            self.depth.label()
+        );
+        let summary = if self.haunted {
+            format!("{summary} · Haunted")
+        } else {
+            summary
+        };
+        let summary = if self.guaranteed_clear && self.supports_guaranteed_clear() {
+            format!("{summary} · Guaranteed clear")
+        } else {
+            summary
+        };
+        match self.style {
+            BoardStyle::Auto => summary,
+            style => format!("{summary} · {}", style.label()),
+        }
+    }
+}
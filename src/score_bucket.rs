@@ -0,0 +1,44 @@
+use strum::IntoEnumIterator;
+
+use crate::{
+    board_style::BoardStyle, difficulty::Difficulty, grid_size::GridSize, orientation::Orientation,
+    rules::GameRules,
+};
+
+/// What a high score is filed under. Scores are only comparable between games on boards of
+/// the same size, dealt from the same number of block types, under the same rules.
+#[derive(Copy, Clone, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+pub struct ScoreBucket {
+    /// The longer side of the board, so both orientations of a board share scores
+    pub rows: u32,
+    pub cols: u32,
+    pub colors: u32,
+    pub rules: GameRules,
+}
+
+impl ScoreBucket {
+    pub fn new(grid_size: GridSize, difficulty: Difficulty, mut rules: GameRules) -> Self {
+        rules.guaranteed_clear &= rules.supports_guaranteed_clear();
+        rules = rules.with_shape(rules.shape);
+
+        // Games are filed under the style their boards were actually dealt in, so Auto
+        // shares scores with whichever style it deals exactly like at this difficulty
+        if rules.style == BoardStyle::Auto {
+            let strategy = difficulty.generation_strategy(BoardStyle::Auto);
+            if let Some(style) = BoardStyle::iter()
+                .filter(|style| *style != BoardStyle::Auto)
+                .find(|style| difficulty.generation_strategy(*style) == strategy)
+            {
+                rules.style = style;
+            }
+        }
+
+        let (rows, cols) = grid_size.grid_dims(Orientation::Portrait);
+        Self {
+            rows: rows.max(cols),
+            cols: rows.min(cols),
+            colors: difficulty.block_type_count() as u32,
+            rules,
+        }
+    }
+}
@@ -0,0 +1,127 @@
+use macroquad::{
+    color::Color,
+    math::Vec2,
+    text::{Font, TextParams, draw_text_ex, measure_text},
+};
+
+use crate::{
+    cleared_group::ClearedGroup,
+    constants::popups::{
+        CALLOUT_COLOR, CALLOUT_DURATION, CALLOUT_TEXT_SIZE, CALLOUTS, POINTS_COLOR,
+        POINTS_DURATION, POINTS_TEXT_SIZE, RISE,
+    },
+    grid_layout::GridLayout,
+};
+
+/// Points earned by each clear, floating up from where the group was
+pub struct ScorePopups {
+    popups: Vec<ScorePopup>,
+}
+
+struct ScorePopup {
+    /// Middle of the cleared group, relative to the board so it stays in place when the
+    /// board is resized
+    anchor: Vec2,
+    points: u32,
+    /// Shown above the points for large groups
+    callout: Option<&'static str>,
+    /// Seconds since the group was cleared
+    age: f32,
+}
+
+impl ScorePopup {
+    fn duration(&self) -> f32 {
+        if self.callout.is_some() {
+            CALLOUT_DURATION
+        } else {
+            POINTS_DURATION
+        }
+    }
+}
+
+impl ScorePopups {
+    pub fn new() -> Self {
+        Self { popups: vec![] }
+    }
+
+    /// Adds a popup for a cleared group. Groups that earned nothing get none.
+    pub fn spawn(&mut self, layout: &GridLayout, group: &ClearedGroup) {
+        if group.points == 0 || group.blocks.is_empty() {
+            return;
+        }
+
+        let centroid = group
+            .blocks
+            .iter()
+            .map(|(position, _)| layout.cell_center(*position))
+            .sum::<Vec2>()
+            / group.blocks.len() as f32;
+        let group_size = group.blocks.len() as u32;
+        self.popups.push(ScorePopup {
+            anchor: layout.world_to_board(centroid),
+            points: group.points,
+            callout: CALLOUTS
+                .iter()
+                .rev()
+                .find(|(min_size, _)| group_size >= *min_size)
+                .map(|(_, callout)| *callout),
+            age: 0.0,
+        });
+    }
+
+    pub fn update(&mut self, time_delta: f32) {
+        for popup in &mut self.popups {
+            popup.age += time_delta;
+        }
+        self.popups.retain(|popup| popup.age < popup.duration());
+    }
+
+    pub fn clear(&mut self) {
+        self.popups.clear();
+    }
+
+    pub fn render(&self, layout: &GridLayout, body_font: &Font, title_font: &Font) {
+        for popup in &self.popups {
+            let t = popup.age / popup.duration();
+            // Holds steady, then fades out over the second half
+            let alpha = (2.0 - 2.0 * t).min(1.0);
+            let rise = RISE * layout.block_size * (1.0 - (1.0 - t).powi(2));
+            let center = layout.board_to_world(popup.anchor) - Vec2::new(0.0, rise);
+
+            let points = format!("+{}", popup.points);
+            let dims = measure_text(&points, Some(body_font), POINTS_TEXT_SIZE, 1.0);
+            draw_text_ex(
+                &points,
+                center.x - dims.width / 2.0,
+                center.y - dims.height / 2.0 + dims.offset_y,
+                TextParams {
+                    font: Some(body_font),
+                    font_size: POINTS_TEXT_SIZE,
+                    color: Color {
+                        a: alpha,
+                        ..POINTS_COLOR
+                    },
+                    ..Default::default()
+                },
+            );
+
+            if let Some(callout) = popup.callout {
+                let callout_dims = measure_text(callout, Some(title_font), CALLOUT_TEXT_SIZE, 1.0);
+                draw_text_ex(
+                    callout,
+                    center.x - callout_dims.width / 2.0,
+                    center.y - dims.height / 2.0 - callout_dims.height + callout_dims.offset_y,
+                    TextParams {
+                        font: Some(title_font),
+                        font_size: CALLOUT_TEXT_SIZE,
+                        color: Color {
+                            a: alpha,
+                            ..CALLOUT_COLOR
+                        },
+                        ..Default::default()
+                    },
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,125 @@
+use strum::EnumIter;
+
+use crate::constants::scoring::{BOARD_CLEAR_BONUS, COMBO_GROUP_SIZE, REMAINING_BLOCK_PENALTY};
+
+#[derive(
+    Copy, Clone, PartialEq, Eq, Hash, Default, EnumIter, serde::Serialize, serde::Deserialize,
+)]
+pub enum ScoringRule {
+    #[default]
+    Classic,
+    Reduced,
+    Linear,
+    Combo,
+}
+
+impl ScoringRule {
+    pub fn label(&self) -> &str {
+        match self {
+            ScoringRule::Classic => "Classic",
+            ScoringRule::Reduced => "Reduced",
+            ScoringRule::Linear => "Linear",
+            ScoringRule::Combo => "Combo",
+        }
+    }
+
+    pub fn formula_hint(&self) -> &str {
+        match self {
+            ScoringRule::Classic => "(n-1)²",
+            ScoringRule::Reduced => "(n-2)²",
+            ScoringRule::Linear => "n-1",
+            ScoringRule::Combo => "(n-1)² × streak",
+        }
+    }
+
+    /// Points for clearing a group of `group_size` blocks, where `streak` is the number of
+    /// consecutive large clears ending with this one, or 0 if this clear isn't large
+    pub fn points_for_clear(&self, group_size: u32, streak: u32) -> u32 {
+        match self {
+            ScoringRule::Classic => group_size.saturating_sub(1).pow(2),
+            ScoringRule::Reduced => group_size.saturating_sub(2).pow(2),
+            ScoringRule::Linear => group_size.saturating_sub(1),
+            ScoringRule::Combo => group_size.saturating_sub(1).pow(2) * streak.max(1),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Default)]
+pub struct ScoreBreakdown {
+    pub clears: u32,
+    pub board_clear_bonus: u32,
+    pub remaining_penalty: u32,
+    pub blocks_remaining: u32,
+}
+
+impl ScoreBreakdown {
+    pub fn total(&self) -> u32 {
+        (self.clears + self.board_clear_bonus).saturating_sub(self.remaining_penalty)
+    }
+}
+
+pub struct ScoreKeeper {
+    rule: ScoringRule,
+    streak: u32,
+    breakdown: ScoreBreakdown,
+}
+
+impl ScoreKeeper {
+    pub fn new(rule: ScoringRule) -> Self {
+        Self {
+            rule,
+            streak: 0,
+            breakdown: ScoreBreakdown::default(),
+        }
+    }
+
+    /// Scores a cleared group and returns the points it earned
+    pub fn record_clear(&mut self, group_size: u32) -> u32 {
+        if group_size == 0 {
+            return 0;
+        }
+
+        // A small clear ends the streak, so it never earns the multiplier itself
+        if group_size >= COMBO_GROUP_SIZE {
+            self.streak += 1;
+        } else {
+            self.streak = 0;
+        }
+        let points = self.rule.points_for_clear(group_size, self.streak);
+
+        self.breakdown.clears += points;
+        points
+    }
+
+    /// Scores a group cleared automatically at step `chain` of a cascade. Each step
+    /// multiplies the group's points by one more than the last. Large groups share the
+    /// player's current streak without extending it.
+    pub fn record_chain_clear(&mut self, group_size: u32, chain: u32) -> u32 {
+        let streak = if group_size >= COMBO_GROUP_SIZE {
+            self.streak
+        } else {
+            0
+        };
+        let points = self.rule.points_for_clear(group_size, streak) * (chain + 1);
+        self.breakdown.clears += points;
+        points
+    }
+
+    /// Applies the end-of-game adjustments for the blocks left on the board
+    pub fn finish(&mut self, blocks_remaining: u32) {
+        self.breakdown.blocks_remaining = blocks_remaining;
+        if blocks_remaining == 0 {
+            self.breakdown.board_clear_bonus = BOARD_CLEAR_BONUS;
+        } else {
+            self.breakdown.remaining_penalty = blocks_remaining * REMAINING_BLOCK_PENALTY;
+        }
+    }
+
+    pub fn score(&self) -> u32 {
+        self.breakdown.total()
+    }
+
+    pub fn breakdown(&self) -> ScoreBreakdown {
+        self.breakdown
+    }
+}
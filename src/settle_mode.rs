@@ -0,0 +1,46 @@
+use strum::EnumIter;
+
+/// How blocks move into place after a clear. This only changes how the board looks while it
+/// settles, never where blocks end up.
+#[derive(Copy, Clone, PartialEq, Default, EnumIter)]
+pub enum SettleMode {
+    /// Blocks fall, then columns shift once every block has landed
+    #[default]
+    Sequential,
+    /// Blocks fall and shift in one move, turning the corner where they land
+    LShaped,
+    /// Blocks move straight to where they end up
+    Diagonal,
+    /// Like diagonal, but every move takes the same short time however far it goes
+    Fast,
+}
+
+impl SettleMode {
+    pub fn label(&self) -> &str {
+        match self {
+            SettleMode::Sequential => "Two steps",
+            SettleMode::LShaped => "L-shaped",
+            SettleMode::Diagonal => "Diagonal",
+            SettleMode::Fast => "Fast",
+        }
+    }
+
+    pub fn hint(&self) -> &str {
+        match self {
+            SettleMode::Sequential => "Fall, then shift",
+            SettleMode::LShaped => "Fall and shift at once",
+            SettleMode::Diagonal => "Straight into place",
+            SettleMode::Fast => "Fixed short time",
+        }
+    }
+
+    /// Whether falls and shifts are animated as one move
+    pub fn combines_moves(&self) -> bool {
+        *self != SettleMode::Sequential
+    }
+
+    /// Whether blocks that fall and shift follow the fall to its end before shifting
+    pub fn turns_corners(&self) -> bool {
+        *self == SettleMode::LShaped
+    }
+}
@@ -5,7 +5,7 @@ use macroquad::{
     texture::{DrawTextureParams, Texture2D, draw_texture_ex},
 };
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy)]
 pub struct SpriteId(pub u32, pub u32);
 
 pub struct SpriteSheet {
@@ -16,11 +16,13 @@ pub struct SpriteSheet {
 }
 
 impl SpriteSheet {
-    pub fn new(bytes: &[u8], rows: u32, cols: u32, size: f32) -> Self {
+    /// Splits the image into as many `size` pixel squares as fit
+    pub fn new(bytes: &[u8], size: f32) -> Self {
+        let sprite_sheet = Texture2D::from_file_with_format(bytes, Some(ImageFormat::Png));
         Self {
-            sprite_sheet: Texture2D::from_file_with_format(bytes, Some(ImageFormat::Png)),
-            rows,
-            cols,
+            rows: (sprite_sheet.height() / size) as u32,
+            cols: (sprite_sheet.width() / size) as u32,
+            sprite_sheet,
             sprite_size: size,
         }
     }
@@ -0,0 +1,62 @@
+use macroquad::{
+    color::{Color, WHITE},
+    math::vec2,
+    texture::{DrawTextureParams, RenderTarget, draw_texture_ex},
+    window::{screen_height, screen_width},
+};
+
+use crate::{constants::transition::DURATION, easing::Easing, transition_kind::TransitionKind};
+
+/// A change of screen in progress. The new screen is drawn as usual and the old one, captured
+/// as it was last drawn, is laid over it.
+pub struct Transition {
+    pub kind: TransitionKind,
+    outgoing: RenderTarget,
+    elapsed: f32,
+}
+
+impl Transition {
+    pub fn new(kind: TransitionKind, outgoing: RenderTarget) -> Self {
+        Self {
+            kind,
+            outgoing,
+            elapsed: 0.0,
+        }
+    }
+
+    /// Advances the transition. Returns true while it is still running.
+    pub fn update(&mut self, time_delta: f32) -> bool {
+        self.elapsed += time_delta;
+        self.elapsed < DURATION
+    }
+
+    /// How far along the transition is, eased, from 0.0 to 1.0
+    pub fn progress(&self) -> f32 {
+        Easing::EaseOut.apply(self.elapsed / DURATION)
+    }
+
+    /// Draws what is left of the old screen over the new one
+    pub fn render(&self) {
+        let (sw, sh) = (screen_width(), screen_height());
+        let t = self.progress();
+        let (x, alpha) = match self.kind {
+            TransitionKind::Fade => (0.0, 1.0 - t),
+            TransitionKind::Slide { back: false } => (-sw * t, 1.0 - t),
+            TransitionKind::Slide { back: true } => (sw * t, 1.0 - t),
+            // The blur is drawn behind the new screen, so nothing is laid over it
+            TransitionKind::BlurIn => return,
+        };
+
+        draw_texture_ex(
+            &self.outgoing.texture,
+            x,
+            0.0,
+            Color { a: alpha, ..WHITE },
+            DrawTextureParams {
+                dest_size: Some(vec2(sw, sh)),
+                flip_y: true,
+                ..Default::default()
+            },
+        );
+    }
+}
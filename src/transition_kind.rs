@@ -0,0 +1,37 @@
+use crate::app::AppState;
+
+/// How one screen gives way to the next
+#[derive(Copy, Clone, PartialEq)]
+pub enum TransitionKind {
+    /// The old screen fades out over the new one
+    Fade,
+    /// The old screen slides off to the left, or to the right when `back` is set
+    Slide { back: bool },
+    /// The board stays behind the new screen and blurs out of focus
+    BlurIn,
+}
+
+impl TransitionKind {
+    /// The transition used when leaving `from` for `to`
+    pub fn between(from: AppState, to: AppState) -> TransitionKind {
+        let is_menu_page = |state| {
+            matches!(
+                state,
+                AppState::Settings
+                    | AppState::HighScores
+                    | AppState::LevelSelect
+                    | AppState::EditorSetup
+            )
+        };
+
+        // Pages opened from the main menu or the pause overlay slide over them
+        let is_hub = |state| matches!(state, AppState::MainMenu | AppState::Paused);
+
+        match (from, to) {
+            (AppState::Playing, AppState::MainMenu | AppState::Paused) => TransitionKind::BlurIn,
+            (from, to) if is_hub(from) && is_menu_page(to) => TransitionKind::Slide { back: false },
+            (from, to) if is_menu_page(from) && is_hub(to) => TransitionKind::Slide { back: true },
+            _ => TransitionKind::Fade,
+        }
+    }
+}
@@ -68,8 +68,8 @@ pub struct WindowChrome {
     initialized: bool,
     resize_state: Option<ResizeState>,
     drag_state: Option<DragState>,
-    grid_rows: u32,
-    grid_cols: u32,
+    /// Size of the grid measured in cells
+    grid_extent: Vec2,
     panel_h: f32,
     close_hovered: bool,
     minimize_hovered: bool,
@@ -81,13 +81,12 @@ pub struct WindowChrome {
 }
 
 impl WindowChrome {
-    pub fn new(grid_rows: u32, grid_cols: u32, panel_h: f32) -> Self {
+    pub fn new(grid_extent: Vec2, panel_h: f32) -> Self {
         Self {
             initialized: false,
             resize_state: None,
             drag_state: None,
-            grid_rows,
-            grid_cols,
+            grid_extent,
             panel_h,
             close_hovered: false,
             minimize_hovered: false,
@@ -102,11 +101,11 @@ impl WindowChrome {
     /// Given a window width, returns the height that makes the grid fit exactly.
     fn width_to_height(&self, w: f32) -> f32 {
         let avail_w = (w - WINDOW_PADDING.x * 2.0 - CONTAINER_INNER_PADDING * 2.0).max(0.0);
-        let block = avail_w / self.grid_cols as f32;
+        let block = avail_w / self.grid_extent.x;
         CHROME_HEIGHT
             + WINDOW_PADDING.y * 2.0
             + CONTAINER_INNER_PADDING * 2.0
-            + self.grid_rows as f32 * block
+            + self.grid_extent.y * block
             + self.panel_h
     }
 
@@ -118,8 +117,8 @@ impl WindowChrome {
             - CONTAINER_INNER_PADDING * 2.0
             - self.panel_h)
             .max(0.0);
-        let block = avail_h / self.grid_rows as f32;
-        WINDOW_PADDING.x * 2.0 + CONTAINER_INNER_PADDING * 2.0 + self.grid_cols as f32 * block
+        let block = avail_h / self.grid_extent.y;
+        WINDOW_PADDING.x * 2.0 + CONTAINER_INNER_PADDING * 2.0 + self.grid_extent.x * block
     }
 
     fn traffic_light_centers() -> [(f32, f32); 3] {
@@ -405,13 +404,11 @@ impl WindowChrome {
         &mut self,
         screen_w: f32,
         screen_h: f32,
-        rows: u32,
-        cols: u32,
+        grid_extent: Vec2,
         panel_h: f32,
         orientation: Orientation,
     ) {
-        self.grid_rows = rows;
-        self.grid_cols = cols;
+        self.grid_extent = grid_extent;
         self.panel_h = panel_h;
 
         let (new_w, new_h) = match orientation {